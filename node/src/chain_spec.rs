@@ -1,7 +1,8 @@
 use metablockchain_runtime::{
-    did::DidStruct, AccountId, AuraConfig, BalancesConfig, CouncilConfig, DidConfig, GenesisConfig,
-    GrandpaConfig, NodeAuthorizationConfig, Signature, SudoConfig, SystemConfig,
-    ValidatorSetConfig, WASM_BINARY,
+    did::DidStruct, tokens::TokenBalance, vc, AccountId, AuraConfig, BalancesConfig,
+    CouncilConfig, DidConfig, GenesisConfig, GrandpaConfig, Hash, NodeAuthorizationConfig,
+    Signature, SudoConfig, SystemConfig, TokensConfig, VCConfig, ValidatorSetConfig,
+    VerifiedCredentialConfig, TREASURY_RESERVE_AMOUNT, WASM_BINARY,
 };
 use sc_service::{ChainType, Properties};
 use serde_json::map::Map;
@@ -10,7 +11,8 @@ use sp_core::crypto::Ss58Codec;
 use sp_core::OpaquePeerId; // A struct wraps Vec<u8>, represents as our `PeerId`.
 use sp_core::{sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
-use sp_runtime::traits::{IdentifyAccount, Verify};
+use sp_runtime::codec::Encode;
+use sp_runtime::traits::{BlakeTwo256, Hash as HashT, IdentifyAccount, Verify};
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
@@ -50,6 +52,56 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
     (get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Convert a string to a fixed size, zero padded byte array
+fn to_fixed_bytes<const N: usize>(value: &str) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes[..value.len()].copy_from_slice(value.as_bytes());
+    bytes
+}
+
+/// Generate a TokenVC owned and signed by the given DID, along with its VC id
+///
+/// sr25519 signatures are randomized, so the signature of the owner over the VC hash is
+/// precomputed to keep the genesis hash stable. The vc pallet rejects the spec at genesis if
+/// the signature doesn't match the VC.
+pub fn token_vc(
+    owner: [u8; 32],
+    token_name: &str,
+    currency_code: &str,
+    decimal: u8,
+    signature: &str,
+) -> (vc::VCHash, vc::VCid) {
+    let signature = sr25519::Signature::from_slice(
+        &sp_core::bytes::from_hex(signature).expect("static values are valid; qed"),
+    );
+    let token_vc = vc::TokenVC {
+        token_name: to_fixed_bytes::<16>(token_name),
+        reservable_balance: TREASURY_RESERVE_AMOUNT,
+        decimal,
+        currency_code: to_fixed_bytes::<8>(currency_code),
+        max_supply: None,
+    };
+    let mut vc_property = [0; 128];
+    let encoded_token_vc = token_vc.encode();
+    vc_property[..encoded_token_vc.len()].copy_from_slice(&encoded_token_vc);
+
+    let vc_type = vc::VCType::TokenVC;
+    let issuers = vec![owner];
+    let hash = BlakeTwo256::hash_of(&(&vc_type, &vc_property, &owner, &issuers));
+
+    let token_vc: vc::VC<Hash> = vc::VC {
+        hash,
+        owner,
+        issuers,
+        signatures: vec![signature],
+        is_vc_used: false,
+        vc_type,
+        vc_property,
+    };
+    let vc_id = *BlakeTwo256::hash_of(&token_vc).as_fixed_bytes();
+    (token_vc.encode(), vc_id)
+}
+
 // specify chain properties
 fn get_common_properties_map() -> Properties {
     let mut properties = Map::new();
@@ -88,6 +140,19 @@ pub fn development_config() -> Result<ChainSpec, String> {
                 }],
                 initial_nodes: vec![], // development chain does not need nodes
                 initial_collective_members: vec![],
+                initial_tokens: vec![(
+                    // signed by `//Alice`
+                    token_vc(
+                        *b"did:ssid:swn\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                        "Development",
+                        "DEV",
+                        6,
+                        "0x34523c5302bef3e3554bdc82fc272c17811eb48b93d2a7d48fe110f7728cda5f\
+                         5a98312161090cf83f6d5a3e0e6b601c52a7baf64c8ef2ae8f83d09ddd7ea788",
+                    ),
+                    // 1 Billion DEV tokens
+                    1_000_000_000_000_000,
+                )],
                 _enable_println: true,
             }
             .build()
@@ -153,6 +218,7 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
                     ),
                 ],
                 initial_collective_members: vec![],
+                initial_tokens: vec![],
                 _enable_println: true,
             }
             .build()
@@ -218,6 +284,7 @@ pub fn mainnet_config() -> Result<ChainSpec, String> {
                     ),
                 ],
                 initial_collective_members: vec![],
+                initial_tokens: vec![],
                 _enable_println: true,
             }
             .build()
@@ -244,6 +311,7 @@ struct GenesisBuilder {
     initial_dids: Vec<DidStruct>,
     initial_nodes: Vec<(OpaquePeerId, [u8; 32])>,
     initial_collective_members: Vec<[u8; 32]>,
+    initial_tokens: Vec<((vc::VCHash, vc::VCid), TokenBalance)>,
     _enable_println: bool,
 }
 
@@ -295,6 +363,23 @@ impl GenesisBuilder {
                 members: self.initial_collective_members,
                 // phantom: Default::default(),
             }),
+            vc: Some(VCConfig {
+                init_vcs: self
+                    .initial_tokens
+                    .iter()
+                    .map(|((token_vc, _), _)| token_vc.clone())
+                    .collect(),
+            }),
+            tokens: Some(TokensConfig {
+                endowed_accounts: vec![],
+                // credit the entire supply of each genesis token to its TokenVC owner
+                issued_tokens: self
+                    .initial_tokens
+                    .iter()
+                    .map(|((_, vc_id), supply)| (*vc_id, *supply))
+                    .collect(),
+            }),
+            verified_credential: Some(VerifiedCredentialConfig::default()),
         }
     }
 }
@@ -7,42 +7,55 @@
 
 use std::sync::Arc;
 
-use metablockchain_runtime::{opaque::Block, AccountId, Balance, Index};
+use metablockchain_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Index};
 pub use sc_rpc_api::DenyUnsafe;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::offchain::OffchainStorage;
 use sp_transaction_pool::TransactionPool;
 
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, S> {
     /// The client instance to use.
     pub client: Arc<C>,
     /// Transaction pool instance.
     pub pool: Arc<P>,
     /// Whether to deny unsafe calls
     pub deny_unsafe: DenyUnsafe,
+    /// Offchain storage of the node, holding indexed schema content
+    pub offchain_storage: Option<S>,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(deps: FullDeps<C, P>) -> jsonrpc_core::IoHandler<sc_rpc::Metadata>
+pub fn create_full<C, P, S>(deps: FullDeps<C, P, S>) -> jsonrpc_core::IoHandler<sc_rpc::Metadata>
 where
     C: ProvideRuntimeApi<Block>,
     C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: vc_rpc::VcRuntimeApi<Block>,
+    C::Api: schema_rpc::SchemaRuntimeApi<Block, Hash>,
+    C::Api: tokens_rpc::TokensRuntimeApi<Block>,
+    C::Api: vesting_rpc::VestingRuntimeApi<Block, BlockNumber>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
+    S: OffchainStorage + 'static,
 {
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
+    use schema_rpc::{Schema, SchemaApi};
+    use tokens_rpc::{Tokens, TokensApi};
+    use vesting_rpc::{Vesting, VestingApi};
+    use vc_rpc::{Vc, VcApi};
 
     let mut io = jsonrpc_core::IoHandler::default();
     let FullDeps {
         client,
         pool,
         deny_unsafe,
+        offchain_storage,
     } = deps;
 
     io.extend_with(SystemApi::to_delegate(FullSystem::new(
@@ -55,6 +68,14 @@ where
         client.clone(),
     )));
 
+    io.extend_with(VcApi::to_delegate(Vc::new(client.clone())));
+
+    io.extend_with(SchemaApi::to_delegate(Schema::new(client.clone(), offchain_storage)));
+
+    io.extend_with(TokensApi::to_delegate(Tokens::new(client.clone())));
+
+    io.extend_with(VestingApi::to_delegate(Vesting::new(client.clone())));
+
     // Extend this RPC with a custom API by using the following syntax.
     // `YourRpcStruct` should have a reference to a client, which is needed
     // to call into the runtime.
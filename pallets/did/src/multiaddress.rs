@@ -26,7 +26,7 @@ where
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             MultiAddress::Did(inner) => write!(f, "{}", HexDisplay::from(inner)),
-            MultiAddress::Id(_inner) => write!(f, "{}", self),
+            MultiAddress::Id(inner) => write!(f, "{}", inner),
         }
     }
 }
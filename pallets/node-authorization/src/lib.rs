@@ -114,6 +114,9 @@ pub trait Config: frame_system::Config + did::Config {
     /// The origin which can reset the well known nodes.
     type ResetOrigin: EnsureOrigin<Self::Origin>;
 
+    /// The origin which can claim a node, resolving to the claiming account.
+    type ClaimOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+
     /// Weight information for extrinsics in this pallet.
     type WeightInfo: WeightInfo;
 }
@@ -296,7 +299,7 @@ decl_module! {
         /// - `node`: identifier of the node.
         #[weight = T::WeightInfo::claim_node()]
         pub fn claim_node(origin, node: PeerId) {
-            let sender = ensure_signed(origin)?;
+            let sender = T::ClaimOrigin::ensure_origin(origin)?;
             let sender_did = did::Module::<T>::get_did_from_account_id(&sender);
             ensure!(node.0.len() < T::MaxPeerIdLength::get() as usize, Error::<T>::PeerIdTooLong);
             ensure!(!Owners::contains_key(&node),Error::<T>::AlreadyClaimed);
@@ -0,0 +1,22 @@
+//! Runtime API definition for the Schema pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+use codec::Codec;
+use did::Did;
+use schema::SchemaDetails;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    pub trait SchemaApi<Hash: Codec> {
+        /// Get the schema stored under the given hash
+        fn get_schema(hash: Hash) -> Option<SchemaDetails<Hash>>;
+
+        /// Get all schemas published by the given author
+        fn list_by_author(author: Did) -> Vec<SchemaDetails<Hash>>;
+
+        /// Get every version of the schemas whose name contains the given name
+        fn search_by_name(name: Vec<u8>) -> Vec<SchemaDetails<Hash>>;
+    }
+}
@@ -0,0 +1,341 @@
+//! RPC interface for the Schema pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use did::Did;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use jsonschema::{Draft, JSONSchema};
+use schema::{SchemaDetails, SchemaStatus};
+pub use schema_rpc_runtime_api::SchemaApi as SchemaRuntimeApi;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+    blake2_256,
+    offchain::{OffchainStorage, STORAGE_PREFIX},
+    Bytes,
+};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+/// Id of the meta-schema the schemas are checked against
+const META_SCHEMA_ID: &str = "http://json-schema.org/draft-07/schema";
+
+/// JSON Schema draft-07 meta-schema, embedded so the check never needs the network
+const META_SCHEMA: &str = include_str!("../meta_schemas/draft-07.json");
+
+/// A schema with its JSON content decoded
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaResponse<Hash> {
+    pub hash: Hash,
+    pub author: String,
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub previous: Option<Hash>,
+    pub status: Option<SchemaStatus>,
+    /// URI/CID of the content when it is kept off-chain, `json` is null in that case
+    pub uri: Option<String>,
+    pub json: Value,
+}
+
+impl<Hash> From<SchemaDetails<Hash>> for SchemaResponse<Hash> {
+    fn from(details: SchemaDetails<Hash>) -> Self {
+        let (name, version, previous, status) = match details.metadata {
+            Some(metadata) => (
+                Some(String::from_utf8_lossy(&metadata.name).into_owned()),
+                Some(format!(
+                    "{}.{}.{}",
+                    metadata.version.major, metadata.version.minor, metadata.version.patch
+                )),
+                metadata.previous,
+                Some(metadata.status),
+            ),
+            None => (None, None, None, None),
+        };
+        let uri = details
+            .uri
+            .map(|uri| String::from_utf8_lossy(&uri).into_owned());
+        let json = if uri.is_some() {
+            Value::Null
+        } else {
+            // schemas stored before on-chain validation may not be valid JSON
+            let json_data = &details.json_data;
+            serde_json::from_slice(json_data).unwrap_or_else(|_| {
+                Value::String(String::from_utf8_lossy(json_data).into_owned())
+            })
+        };
+        SchemaResponse {
+            hash: details.hash,
+            author: String::from_utf8_lossy(&details.author)
+                .trim_end_matches('\0')
+                .to_string(),
+            name,
+            version,
+            previous,
+            status,
+            uri,
+            json,
+        }
+    }
+}
+
+#[rpc]
+pub trait SchemaApi<BlockHash, Hash> {
+    /// Check the given schema against the JSON Schema draft-07 meta-schema
+    ///
+    /// Returns the list of violations, empty if the schema is valid
+    #[rpc(name = "schema_check_meta_schema")]
+    fn check_meta_schema(&self, json_data: Bytes) -> Result<Vec<String>>;
+
+    /// Get the schema stored under the given hash
+    #[rpc(name = "schema_get")]
+    fn get(&self, hash: Hash, at: Option<BlockHash>) -> Result<Option<SchemaResponse<Hash>>>;
+
+    /// Get all schemas published by the given author DID
+    #[rpc(name = "schema_list_by_author")]
+    fn list_by_author(
+        &self,
+        author: String,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<SchemaResponse<Hash>>>;
+
+    /// Get every version of the schemas whose name contains the given name
+    #[rpc(name = "schema_search_by_name")]
+    fn search_by_name(
+        &self,
+        name: String,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<SchemaResponse<Hash>>>;
+
+    /// Get the content of a schema, verified against its on-chain hash
+    ///
+    /// Content of schemas stored off-chain is read from the node's offchain database, which is
+    /// only populated when the node runs with offchain indexing enabled
+    #[rpc(name = "schema_get_content")]
+    fn get_content(&self, hash: Hash, at: Option<BlockHash>) -> Result<Option<Bytes>>;
+}
+
+/// A struct that implements the `SchemaApi`.
+pub struct Schema<C, B, S> {
+    client: Arc<C>,
+    offchain_storage: Option<S>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B, S> Schema<C, B, S> {
+    /// Create new `Schema` instance with the given reference to the client and the node's
+    /// offchain storage, if any.
+    pub fn new(client: Arc<C>, offchain_storage: Option<S>) -> Self {
+        Self {
+            client,
+            offchain_storage,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The schema is not valid JSON.
+    InvalidJson,
+    /// The meta-schema could not be loaded.
+    MetaSchemaError,
+    /// The call to runtime failed.
+    RuntimeError,
+    /// The given DID is too long.
+    InvalidDid,
+    /// The off-chain content is missing or doesn't match the on-chain hash.
+    ContentUnavailable,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::InvalidJson => 1,
+            Error::MetaSchemaError => 2,
+            Error::RuntimeError => 3,
+            Error::InvalidDid => 4,
+            Error::ContentUnavailable => 5,
+        }
+    }
+}
+
+fn runtime_error<E: std::fmt::Debug>(e: E) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(Error::RuntimeError.into()),
+        message: "Unable to query schemas.".into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+fn content_unavailable(message: &str) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(Error::ContentUnavailable.into()),
+        message: message.into(),
+        data: None,
+    }
+}
+
+/// Check the schema against the embedded draft-07 meta-schema and list the violations
+fn meta_schema_violations(schema: &Value) -> std::result::Result<Vec<String>, String> {
+    let meta_schema: Value = serde_json::from_str(META_SCHEMA).map_err(|e| format!("{:?}", e))?;
+    let compiled = JSONSchema::options()
+        .with_draft(Draft::Draft7)
+        // the meta-schema refers to itself by its id
+        .with_document(META_SCHEMA_ID.into(), meta_schema.clone())
+        .compile(&meta_schema)
+        .map_err(|e| format!("{:?}", e))?;
+
+    let violations = match compiled.validate(schema) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors.map(|e| e.to_string()).collect(),
+    };
+    Ok(violations)
+}
+
+impl<C, Block, Hash, S> SchemaApi<<Block as BlockT>::Hash, Hash> for Schema<C, Block, S>
+where
+    Block: BlockT,
+    Hash: Codec + AsRef<[u8]> + Send + Sync + Serialize + 'static,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: SchemaRuntimeApi<Block, Hash>,
+    S: OffchainStorage + 'static,
+{
+    fn check_meta_schema(&self, json_data: Bytes) -> Result<Vec<String>> {
+        let schema: Value = serde_json::from_slice(&json_data).map_err(|e| RpcError {
+            code: ErrorCode::ServerError(Error::InvalidJson.into()),
+            message: "Schema is not valid JSON.".into(),
+            data: Some(format!("{:?}", e).into()),
+        })?;
+
+        meta_schema_violations(&schema).map_err(|e| RpcError {
+            code: ErrorCode::ServerError(Error::MetaSchemaError.into()),
+            message: "Unable to load the meta-schema.".into(),
+            data: Some(e.into()),
+        })
+    }
+
+    fn get(
+        &self,
+        hash: Hash,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<SchemaResponse<Hash>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let schema = api.get_schema(&at, hash).map_err(runtime_error)?;
+        Ok(schema.map(Into::into))
+    }
+
+    fn list_by_author(
+        &self,
+        author: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<SchemaResponse<Hash>>> {
+        let mut did: Did = [0; 32];
+        if author.len() > did.len() {
+            return Err(RpcError {
+                code: ErrorCode::ServerError(Error::InvalidDid.into()),
+                message: "DID must be at most 32 bytes.".into(),
+                data: None,
+            });
+        }
+        did[..author.len()].copy_from_slice(author.as_bytes());
+
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let schemas = api.list_by_author(&at, did).map_err(runtime_error)?;
+        Ok(schemas.into_iter().map(Into::into).collect())
+    }
+
+    fn search_by_name(
+        &self,
+        name: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<SchemaResponse<Hash>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let schemas = api
+            .search_by_name(&at, name.into_bytes())
+            .map_err(runtime_error)?;
+        Ok(schemas.into_iter().map(Into::into).collect())
+    }
+
+    fn get_content(
+        &self,
+        hash: Hash,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Bytes>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let details = match api.get_schema(&at, hash).map_err(runtime_error)? {
+            Some(details) => details,
+            None => return Ok(None),
+        };
+        if details.uri.is_none() {
+            return Ok(Some(details.json_data.into()));
+        }
+
+        let storage = self
+            .offchain_storage
+            .as_ref()
+            .ok_or_else(|| content_unavailable("Offchain storage is not available."))?;
+        let content = storage
+            .get(STORAGE_PREFIX, &schema::offchain_key(&details.hash))
+            .ok_or_else(|| content_unavailable("Schema content is not indexed on this node."))?;
+
+        // the runtime hashes schemas with blake2-256
+        if blake2_256(&content)[..] != *details.hash.as_ref() {
+            return Err(content_unavailable("Schema content does not match its hash."));
+        }
+        Ok(Some(content.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn valid_schema_has_no_violations() {
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "$id": "https://example.com/person.schema.json",
+            "title": "Person",
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "age": { "type": "integer", "minimum": 0 },
+                "emails": { "type": "array", "items": { "type": "string", "format": "email" } },
+                "address": { "$ref": "#/definitions/address" }
+            },
+            "required": ["name"],
+            "definitions": {
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } },
+                    "additionalProperties": false
+                }
+            }
+        });
+        assert_eq!(meta_schema_violations(&schema), Ok(vec![]));
+    }
+
+    #[test]
+    fn invalid_schema_is_reported() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "age": { "type": "number", "minimum": "zero" } },
+            "required": "age"
+        });
+        assert_eq!(meta_schema_violations(&schema).unwrap().len(), 2);
+        assert!(!meta_schema_violations(&json!({ "type": "unknown" })).unwrap().is_empty());
+    }
+}
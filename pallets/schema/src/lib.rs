@@ -3,22 +3,50 @@
 use did;
 use frame_support::{
     debug, decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
-    StorageMap,
+    traits::{Currency, Get, ReservableCurrency},
+    IterableStorageMap, StorageMap,
 };
 use frame_system::{self, ensure_signed};
+use sp_runtime::traits::{Hash, Saturating};
 use sp_std::prelude::*;
 
 #[cfg(test)]
 mod tests;
+pub mod json;
+mod structs;
+pub use crate::structs::*;
+
+type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 /// The SCHEMA trait
 pub trait Config: frame_system::Config + did::Config {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+    /// Maximum length in bytes of a schema
+    type MaxSchemaLength: Get<u32>;
+
+    /// Currency used to reserve the schema storage deposit
+    type Currency: ReservableCurrency<Self::AccountId>;
+
+    /// Deposit reserved for every stored schema
+    type SchemaDepositBase: Get<BalanceOf<Self>>;
+
+    /// Additional deposit reserved per byte of schema
+    type SchemaDepositPerByte: Get<BalanceOf<Self>>;
 }
 
 decl_event!(
-    pub enum Event<T> where <T as frame_system::Config>::Hash {
+    pub enum Event<T> where <T as frame_system::Config>::Hash, Balance = BalanceOf<T> {
         SchemaCreated(did::Did, Hash),
+        /// A new version of a schema was published (author, new version, previous version)
+        SchemaVersionPublished(did::Did, Hash, Hash),
+        /// The status of a schema version changed
+        SchemaStatusUpdated(Hash, SchemaStatus),
+        /// A schema was removed by its author and the deposit returned
+        SchemaRemoved(Hash),
+        /// A schema was removed by a validator and the deposit slashed
+        SchemaForceRemoved(Hash, Balance),
     }
 );
 
@@ -27,6 +55,26 @@ decl_error! {
         //Schema already exists
         SchemaAlreadyExists,
         NotAValidator,
+        /// Schema is larger than `MaxSchemaLength`
+        SchemaTooLarge,
+        /// Schema is not a UTF-8 encoded JSON object
+        InvalidJson,
+        /// Schema name is empty or longer than `MAX_SCHEMA_NAME_LENGTH`
+        InvalidSchemaName,
+        /// A schema with the same name is already registered
+        SchemaNameAlreadyExists,
+        /// Schema does not exist or has no version information
+        SchemaNotFound,
+        /// Only the author of the schema or a validator can perform this operation
+        NotSchemaAuthor,
+        /// New version must be greater than the previous one and not published yet
+        InvalidVersion,
+        /// Status can only move forward from Active to Deprecated to Retired
+        InvalidStatusTransition,
+        /// Caller has no registered DID
+        DIDNotRegistered,
+        /// URI is empty or longer than `MAX_SCHEMA_URI_LENGTH`
+        InvalidUri,
     }
 }
 
@@ -40,18 +88,35 @@ decl_module! {
 
         /// Function to create a new SCHEMA
         /// origin - the origin of the transaction
-        /// hash - hash of the schema
-        /// json_data - json data of schema as string
+        /// name - name of the schema, shared by all of its versions
+        /// version - semantic version of this first release
+        /// uri - optional URI/CID of the content, when set only the hash and URI are kept
+        /// in state and the content is stored in the node's offchain database
+        /// json_data - json data of schema as string, the schema is keyed by its hash
         #[weight = 1]
-        pub fn add(origin, hash: T::Hash, json_data: Vec<u8>) -> DispatchResult {
+        pub fn add(
+            origin,
+            name: Vec<u8>,
+            version: SchemaVersion,
+            uri: Option<Vec<u8>>,
+            json_data: Vec<u8>,
+        ) -> DispatchResult {
             // origin of the transaction needs to be a signed sender account
             let sender = ensure_signed(origin)?;
 
-            // ensure the caller is a validator account
-            ensure!(did::Module::<T>::is_caller_validator(&sender), Error::<T>::NotAValidator);
+            // ensure the caller has a registered DID
+            ensure!(did::Module::<T>::does_did_exist(&sender), Error::<T>::DIDNotRegistered);
+
+            ensure!(
+                !name.is_empty() && name.len() <= MAX_SCHEMA_NAME_LENGTH,
+                Error::<T>::InvalidSchemaName
+            );
+            ensure!(!SchemaVersions::<T>::contains_key(&name), Error::<T>::SchemaNameAlreadyExists);
+
+            let hash = Self::validate_schema(&json_data)?;
 
             // check if SCHEMA already exists
-            ensure!(!SCHEMA::<T>::contains_key(&hash), Error::<T>::SchemaAlreadyExists);
+            ensure!(!SCHEMA::<T>::contains_key(hash), Error::<T>::SchemaAlreadyExists);
 
             // fetch the DID mapped to the origin accountId - cannot panic since the did has
             // already been confirmed in the previous step
@@ -59,11 +124,119 @@ decl_module! {
 
             // add SCHEMA to storage
             debug::print!("insert SCHEMA");
-            <SCHEMA<T>>::insert(hash, (caller_did, json_data));
+            Self::store_content(&sender, caller_did, hash, uri, json_data)?;
+            SchemasByAuthor::<T>::append(caller_did, hash);
+            SchemaVersions::<T>::insert(&name, vec![(version, hash)]);
+            SchemaInfo::<T>::insert(hash, SchemaMetadata {
+                name,
+                version,
+                previous: None,
+                status: SchemaStatus::Active,
+            });
             // deposit event that the SCHEMA has been added
             Self::deposit_event(RawEvent::SchemaCreated(caller_did, hash));
             Ok(())
         }
+
+        /// Publish a new version of an existing schema
+        /// origin - author of the previous version or a validator
+        /// previous - hash of the version being superseded
+        /// version - semantic version of the new release, greater than the previous one
+        /// uri - optional URI/CID of the content, see `add`
+        /// json_data - json data of the new version
+        #[weight = 1]
+        pub fn publish_version(
+            origin,
+            previous: T::Hash,
+            version: SchemaVersion,
+            uri: Option<Vec<u8>>,
+            json_data: Vec<u8>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_author_or_validator(&sender, &previous)?;
+
+            let previous_info = SchemaInfo::<T>::get(previous).ok_or(Error::<T>::SchemaNotFound)?;
+            ensure!(version > previous_info.version, Error::<T>::InvalidVersion);
+
+            let mut versions = SchemaVersions::<T>::get(&previous_info.name);
+            let position = match versions.binary_search_by(|(v, _)| v.cmp(&version)) {
+                Ok(_) => return Err(Error::<T>::InvalidVersion.into()),
+                Err(position) => position,
+            };
+
+            let hash = Self::validate_schema(&json_data)?;
+            ensure!(!SCHEMA::<T>::contains_key(hash), Error::<T>::SchemaAlreadyExists);
+
+            let caller_did = did::Module::<T>::get_did_from_account_id(&sender);
+            Self::store_content(&sender, caller_did, hash, uri, json_data)?;
+            versions.insert(position, (version, hash));
+
+            SchemasByAuthor::<T>::append(caller_did, hash);
+            SchemaVersions::<T>::insert(&previous_info.name, versions);
+            SchemaInfo::<T>::insert(hash, SchemaMetadata {
+                name: previous_info.name,
+                version,
+                previous: Some(previous),
+                status: SchemaStatus::Active,
+            });
+            Self::deposit_event(RawEvent::SchemaVersionPublished(caller_did, hash, previous));
+            Ok(())
+        }
+
+        /// Deprecate or retire a schema version
+        /// origin - author of the schema or a validator
+        /// hash - hash of the schema version
+        /// status - new status, must come after the current one
+        #[weight = 1]
+        pub fn update_status(origin, hash: T::Hash, status: SchemaStatus) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_author_or_validator(&sender, &hash)?;
+
+            SchemaInfo::<T>::try_mutate(hash, |info| -> DispatchResult {
+                let info = info.as_mut().ok_or(Error::<T>::SchemaNotFound)?;
+                ensure!(status > info.status, Error::<T>::InvalidStatusTransition);
+                info.status = status;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::SchemaStatusUpdated(hash, status));
+            Ok(())
+        }
+
+        /// Remove a schema and return its deposit
+        /// origin - author of the schema
+        /// hash - hash of the schema
+        #[weight = 1]
+        pub fn remove(origin, hash: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let (author, _) = SCHEMA::<T>::get(hash).ok_or(Error::<T>::SchemaNotFound)?;
+            let sender_did = did::Module::<T>::get_did_from_account_id(&sender);
+            ensure!(author == sender_did, Error::<T>::NotSchemaAuthor);
+
+            if let Some((depositor, deposit)) = Self::remove_schema(hash, author) {
+                T::Currency::unreserve(&depositor, deposit);
+            }
+            Self::deposit_event(RawEvent::SchemaRemoved(hash));
+            Ok(())
+        }
+
+        /// Remove an abusive schema and slash its deposit
+        /// origin - a validator
+        /// hash - hash of the schema
+        #[weight = 1]
+        pub fn force_remove(origin, hash: T::Hash) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(did::Module::<T>::is_caller_validator(&sender), Error::<T>::NotAValidator);
+            let (author, _) = SCHEMA::<T>::get(hash).ok_or(Error::<T>::SchemaNotFound)?;
+
+            let mut slashed = BalanceOf::<T>::default();
+            if let Some((depositor, deposit)) = Self::remove_schema(hash, author) {
+                // the slashed imbalance is dropped, burning the deposit
+                let (_, remaining) = T::Currency::slash_reserved(&depositor, deposit);
+                slashed = deposit.saturating_sub(remaining);
+            }
+            Self::deposit_event(RawEvent::SchemaForceRemoved(hash, slashed));
+            Ok(())
+        }
     }
 }
 
@@ -71,5 +244,146 @@ decl_storage! {
     trait Store for Module<T: Config> as Schema {
         // SCHEMA hash -> account_id, json
         pub SCHEMA get(fn schema):map hasher(opaque_blake2_256) T::Hash => Option<(did::Did, Vec<u8>)>;
+        // SCHEMA hash -> name, version, previous version and status
+        pub SchemaInfo get(fn schema_info): map hasher(opaque_blake2_256) T::Hash => Option<SchemaMetadata<T::Hash>>;
+        // SCHEMA name -> (version, hash) of every published version, sorted by version
+        pub SchemaVersions get(fn schema_versions): map hasher(blake2_128_concat) Vec<u8> => Vec<(SchemaVersion, T::Hash)>;
+        // author DID -> hashes of the schemas it published
+        pub SchemasByAuthor get(fn schemas_by_author): map hasher(blake2_128_concat) did::Did => Vec<T::Hash>;
+        // SCHEMA hash -> URI/CID of schemas whose content is kept off-chain
+        pub SchemaUris get(fn schema_uris): map hasher(opaque_blake2_256) T::Hash => Option<Vec<u8>>;
+        // SCHEMA hash -> account that reserved the storage deposit and its amount
+        pub SchemaDeposits get(fn schema_deposits): map hasher(opaque_blake2_256) T::Hash => Option<(T::AccountId, BalanceOf<T>)>;
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Ensure the schema is a well formed JSON object within the size limit and return its hash
+    pub fn validate_schema(json_data: &[u8]) -> Result<T::Hash, Error<T>> {
+        ensure!(
+            json_data.len() <= T::MaxSchemaLength::get() as usize,
+            Error::<T>::SchemaTooLarge
+        );
+        ensure!(json::is_valid_json_object(json_data), Error::<T>::InvalidJson);
+        Ok(T::Hashing::hash(json_data))
+    }
+
+    /// Reserve the deposit and store the schema content, or only its URI when given
+    ///
+    /// The content of URI schemas is written to the offchain database through offchain indexing,
+    /// which requires the node to run with `--enable-offchain-indexing true` to keep it
+    fn store_content(
+        sender: &T::AccountId,
+        author: did::Did,
+        hash: T::Hash,
+        uri: Option<Vec<u8>>,
+        json_data: Vec<u8>,
+    ) -> DispatchResult {
+        match uri {
+            Some(uri) => {
+                ensure!(
+                    !uri.is_empty() && uri.len() <= MAX_SCHEMA_URI_LENGTH,
+                    Error::<T>::InvalidUri
+                );
+                Self::reserve_deposit(sender, hash, uri.len())?;
+                sp_io::offchain_index::set(&offchain_key(&hash), &json_data);
+                <SCHEMA<T>>::insert(hash, (author, Vec::<u8>::new()));
+                SchemaUris::<T>::insert(hash, uri);
+            }
+            None => {
+                Self::reserve_deposit(sender, hash, json_data.len())?;
+                <SCHEMA<T>>::insert(hash, (author, json_data));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reserve the storage deposit of a schema of `length` bytes from the sender
+    fn reserve_deposit(sender: &T::AccountId, hash: T::Hash, length: usize) -> DispatchResult {
+        let deposit = T::SchemaDepositBase::get()
+            .saturating_add(T::SchemaDepositPerByte::get().saturating_mul((length as u32).into()));
+        T::Currency::reserve(sender, deposit)?;
+        SchemaDeposits::<T>::insert(hash, (sender.clone(), deposit));
+        Ok(())
+    }
+
+    /// Remove the schema and every index pointing to it, returning its deposit if any
+    ///
+    /// Later versions keep their link to the removed version
+    fn remove_schema(hash: T::Hash, author: did::Did) -> Option<(T::AccountId, BalanceOf<T>)> {
+        SCHEMA::<T>::remove(hash);
+        if SchemaUris::<T>::take(hash).is_some() {
+            sp_io::offchain_index::clear(&offchain_key(&hash));
+        }
+        SchemasByAuthor::<T>::mutate(author, |hashes| hashes.retain(|h| *h != hash));
+        if let Some(info) = SchemaInfo::<T>::take(hash) {
+            let mut versions = SchemaVersions::<T>::get(&info.name);
+            versions.retain(|(_, h)| *h != hash);
+            if versions.is_empty() {
+                SchemaVersions::<T>::remove(&info.name);
+            } else {
+                SchemaVersions::<T>::insert(&info.name, versions);
+            }
+        }
+        SchemaDeposits::<T>::take(hash)
+    }
+
+    /// Ensure the sender is the author of the schema or a validator
+    fn ensure_author_or_validator(sender: &T::AccountId, hash: &T::Hash) -> DispatchResult {
+        let (author, _) = SCHEMA::<T>::get(hash).ok_or(Error::<T>::SchemaNotFound)?;
+        let sender_did = did::Module::<T>::get_did_from_account_id(sender);
+        ensure!(
+            author == sender_did || did::Module::<T>::is_caller_validator(sender),
+            Error::<T>::NotSchemaAuthor
+        );
+        Ok(())
+    }
+
+    /// Get the status of a schema, schemas stored without version information count as active
+    pub fn schema_status(hash: &T::Hash) -> Option<SchemaStatus> {
+        if !SCHEMA::<T>::contains_key(hash) {
+            return None;
+        }
+        Some(SchemaInfo::<T>::get(hash).map(|info| info.status).unwrap_or_default())
+    }
+
+    /// Get a schema together with its author and version information
+    pub fn get_schema_details(hash: T::Hash) -> Option<SchemaDetails<T::Hash>> {
+        let (author, json_data) = SCHEMA::<T>::get(hash)?;
+        Some(SchemaDetails {
+            hash,
+            author,
+            metadata: SchemaInfo::<T>::get(hash),
+            uri: SchemaUris::<T>::get(hash),
+            json_data,
+        })
+    }
+
+    /// Get all schemas published by the given author
+    ///
+    /// Schemas stored before the author index was introduced are not listed
+    pub fn get_schemas_by_author(author: did::Did) -> Vec<SchemaDetails<T::Hash>> {
+        SchemasByAuthor::<T>::get(author)
+            .into_iter()
+            .filter_map(Self::get_schema_details)
+            .collect()
+    }
+
+    /// Get every version of the schemas whose name contains `name`
+    ///
+    /// Iterates over all schema names, only meant to be called off-chain
+    pub fn search_schemas_by_name(name: Vec<u8>) -> Vec<SchemaDetails<T::Hash>> {
+        SchemaVersions::<T>::iter()
+            .filter(|(schema_name, _)| {
+                name.is_empty() || schema_name.windows(name.len()).any(|window| window == &name[..])
+            })
+            .flat_map(|(_, versions)| versions.into_iter())
+            .filter_map(|(_, hash)| Self::get_schema_details(hash))
+            .collect()
+    }
+
+    /// Check whether the schema has been retired
+    pub fn is_schema_retired(hash: &T::Hash) -> bool {
+        Self::schema_status(hash) == Some(SchemaStatus::Retired)
     }
 }
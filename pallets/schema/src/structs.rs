@@ -0,0 +1,73 @@
+use super::*;
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+
+/// Maximum length in bytes of a schema name
+pub const MAX_SCHEMA_NAME_LENGTH: usize = 64;
+
+/// Maximum length in bytes of the URI/CID of a schema stored off-chain
+pub const MAX_SCHEMA_URI_LENGTH: usize = 256;
+
+/// Prefix of the offchain database keys holding the content of schemas stored off-chain
+pub const OFFCHAIN_PREFIX: &[u8] = b"schema::content::";
+
+/// Offchain database key under which the content of the schema with the given hash is indexed
+pub fn offchain_key<Hash: Encode>(hash: &Hash) -> Vec<u8> {
+    let mut key = OFFCHAIN_PREFIX.to_vec();
+    key.extend(hash.encode());
+    key
+}
+
+/// Semantic version of a schema
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchemaVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+}
+
+/// Lifecycle status of a schema version
+///
+/// A status can only move forward: Active -> Deprecated -> Retired
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SchemaStatus {
+    /// Schema can be used to issue new credentials
+    Active,
+    /// Schema still valid but superseded, issuers should move to a newer version
+    Deprecated,
+    /// Schema must no longer be used, credentials referencing it are flagged
+    Retired,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for SchemaStatus {
+    fn default() -> Self {
+        SchemaStatus::Active
+    }
+}
+
+/// Name, version and status of a stored schema
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchemaMetadata<Hash> {
+    pub name: Vec<u8>,
+    pub version: SchemaVersion,
+    /// Hash of the version this one supersedes
+    pub previous: Option<Hash>,
+    pub status: SchemaStatus,
+}
+
+/// Everything stored about a schema, returned by the schema queries
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchemaDetails<Hash> {
+    pub hash: Hash,
+    pub author: did::Did,
+    /// Version information, `None` for schemas stored before versioning
+    pub metadata: Option<SchemaMetadata<Hash>>,
+    /// URI/CID of the content when it is kept off-chain, `json_data` is empty in that case
+    pub uri: Option<Vec<u8>>,
+    pub json_data: Vec<u8>,
+}
@@ -1,10 +1,9 @@
 // Tests for Schema module
 use super::*;
 use did;
-use frame_support::{assert_noop, assert_ok, parameter_types};
+use frame_support::{assert_noop, assert_ok, parameter_types, traits::StorageMapShim};
 use sp_core::{sr25519, H256};
 use sp_runtime::{testing::Header, traits::BlakeTwo256};
-use std::str::FromStr;
 
 use crate as schema;
 use crate::Config;
@@ -24,6 +23,7 @@ frame_support::construct_runtime!(
         Schema: schema::{Module, Call, Storage, Event<T>},
         Did: did::{Module, Call, Storage, Event, Config},
         ValidatorSet: validator_set::{Module, Call, Storage, Event, Config},
+        Balances: balances::{Module, Call, Storage, Event<T>, Config<T>},
     }
 );
 
@@ -50,15 +50,46 @@ impl frame_system::Config for Test {
     type DbWeight = ();
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
     type SS58Prefix = SS58Prefix;
 }
 
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = StorageMapShim<
+        balances::Account<Test>,
+        frame_system::Provider<Test>,
+        u64,
+        balances::AccountData<u64>,
+    >;
+    type MaxLocks = MaxLocks;
+    type WeightInfo = ();
+    type DidResolution = did::Module<Test>;
+}
+
+parameter_types! {
+    pub const MaxSchemaLength: u32 = MAX_SCHEMA_LENGTH;
+    pub const SchemaDepositBase: u64 = DEPOSIT_BASE;
+    pub const SchemaDepositPerByte: u64 = DEPOSIT_PER_BYTE;
+}
+
 impl Config for Test {
     type Event = Event;
+    type MaxSchemaLength = MaxSchemaLength;
+    type Currency = Balances;
+    type SchemaDepositBase = SchemaDepositBase;
+    type SchemaDepositPerByte = SchemaDepositPerByte;
 }
 
 impl did::Config for Test {
@@ -74,6 +105,13 @@ type DidStruct = did::DidStruct;
 
 const VALIDATOR_ACCOUNT: u64 = 0;
 const NON_VALIDATOR_ACCOUNT: u64 = 2;
+const MAX_SCHEMA_LENGTH: u32 = 1024;
+const DEPOSIT_BASE: u64 = 100;
+const DEPOSIT_PER_BYTE: u64 = 1;
+const INITIAL_BALANCE: u64 = 10_000;
+const AUTHOR_ACCOUNT: u64 = 217020518514230019;
+const AUTHOR_DID: [u8; 32] = *b"did:ssid:author\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const AUTHOR_PUBKEY: sr25519::Public = sr25519::Public([3; 32]);
 const VALIDATOR_DID: [u8; 32] = *b"Alice\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
 const VALIDATOR_PUBKEY: sr25519::Public = sr25519::Public([0; 32]);
 
@@ -100,6 +138,16 @@ fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage::<Test>(&mut o)
     .unwrap();
 
+    balances::GenesisConfig::<Test> {
+        balances: vec![
+            (VALIDATOR_ACCOUNT, INITIAL_BALANCE),
+            (AUTHOR_ACCOUNT, INITIAL_BALANCE),
+            (NON_VALIDATOR_ACCOUNT, INITIAL_BALANCE),
+        ],
+    }
+    .assimilate_storage(&mut o)
+    .unwrap();
+
     o.into()
 }
 
@@ -113,15 +161,25 @@ fn genesis_setup_works() {
     })
 }
 
+const TEST_SCHEMA: &str = r#"{"type": "object", "properties": {"name": {"type": "string"}}}"#;
+const TEST_SCHEMA_V2: &str = r#"{"type": "object", "properties": {"name": {"type": "string"}, "age": {"type": "integer"}}}"#;
+const TEST_SCHEMA_V3: &str = r#"{"type": "object", "required": ["name"]}"#;
+const TEST_SCHEMA_NAME: &[u8] = b"Person";
+
+fn version(major: u16, minor: u16, patch: u16) -> SchemaVersion {
+    SchemaVersion { major, minor, patch }
+}
+
 #[test]
-#[should_panic(expected = "NotAValidator")]
-fn non_validator_should_not_add_schema() {
+#[should_panic(expected = "DIDNotRegistered")]
+fn unregistered_did_should_not_add_schema() {
     new_test_ext().execute_with(|| {
         assert_ok!(Schema::add(
             Origin::signed(NON_VALIDATOR_ACCOUNT),
-            H256::from_str("D04B98F48E8F8BCC15C6AE5AC050801CD6DCFD428FB5F9E65C4E16E7807340FA")
-                .unwrap(),
-            vec![]
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
         ));
     })
 }
@@ -129,40 +187,423 @@ fn non_validator_should_not_add_schema() {
 #[test]
 fn test_add_new_schema() {
     new_test_ext().execute_with(|| {
-        let schema_hash =
-            H256::from_str("D04B98F48E8F8BCC15C6AE5AC050801CD6DCFD428FB5F9E65C4E16E7807340FA")
-                .unwrap();
         assert_ok!(Schema::add(
             Origin::signed(VALIDATOR_ACCOUNT),
-            schema_hash,
-            "TestSchema".as_bytes().to_vec()
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
         ));
 
-        // ensure the stored data matches
+        // ensure the schema is keyed by the hash of its content
+        let schema_hash = BlakeTwo256::hash(TEST_SCHEMA.as_bytes());
         let (origin_acc, schema_data) = SCHEMA::<Test>::get(schema_hash).unwrap();
         assert_eq!(origin_acc, VALIDATOR_DID);
-        assert_eq!(schema_data, "TestSchema".as_bytes().to_vec());
+        assert_eq!(schema_data, TEST_SCHEMA.as_bytes().to_vec());
     })
 }
 
 #[test]
-#[should_panic(expected = "SchemaAlreadyExists")]
+#[should_panic(expected = "SchemaNameAlreadyExists")]
 fn test_repeat_same_schema() {
     new_test_ext().execute_with(|| {
-        let schema_hash =
-            H256::from_str("D04B98F48E8F8BCC15C6AE5AC050801CD6DCFD428FB5F9E65C4E16E7807340FA")
-                .unwrap();
+        assert_ok!(Schema::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+
+        assert_ok!(Schema::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+    })
+}
+
+#[test]
+fn test_reject_invalid_schema() {
+    new_test_ext().execute_with(|| {
+        // not json
+        assert_noop!(
+            Schema::add(
+                Origin::signed(VALIDATOR_ACCOUNT),
+                TEST_SCHEMA_NAME.to_vec(),
+                version(1, 0, 0),
+                None,
+                "TestSchema".as_bytes().to_vec()
+            ),
+            Error::<Test>::InvalidJson
+        );
+        // not utf-8
+        assert_noop!(
+            Schema::add(
+                Origin::signed(VALIDATOR_ACCOUNT),
+                TEST_SCHEMA_NAME.to_vec(),
+                version(1, 0, 0),
+                None,
+                vec![b'{', b'"', 0xff, b'"', b'}']
+            ),
+            Error::<Test>::InvalidJson
+        );
+        // too large
+        let large_schema = format!(r#"{{"description": "{}"}}"#, "a".repeat(MAX_SCHEMA_LENGTH as usize));
+        assert_noop!(
+            Schema::add(
+                Origin::signed(VALIDATOR_ACCOUNT),
+                TEST_SCHEMA_NAME.to_vec(),
+                version(1, 0, 0),
+                None,
+                large_schema.into_bytes()
+            ),
+            Error::<Test>::SchemaTooLarge
+        );
+    })
+}
+
+#[test]
+fn test_json_validation() {
+    assert!(json::is_valid_json_object(TEST_SCHEMA.as_bytes()));
+    assert!(json::is_valid_json_object(
+        br#" {"a": [1, -2.5e+3, 0, true, false, null, "\u00e9\n"], "b": {}} "#
+    ));
+    assert!(json::is_valid_json_object("{\"name\": \"caf\u{e9}\"}".as_bytes()));
+
+    // top level must be an object
+    assert!(!json::is_valid_json_object(b"[]"));
+    assert!(!json::is_valid_json_object(b"\"schema\""));
+    assert!(!json::is_valid_json_object(b""));
+    // malformed documents
+    assert!(!json::is_valid_json_object(br#"{"a": 1,}"#));
+    assert!(!json::is_valid_json_object(br#"{"a" 1}"#));
+    assert!(!json::is_valid_json_object(br#"{"a": 01}"#));
+    assert!(!json::is_valid_json_object(br#"{"a": tru}"#));
+    assert!(!json::is_valid_json_object(br#"{"a": "\x"}"#));
+    assert!(!json::is_valid_json_object(br#"{"a": 1} {}"#));
+    assert!(!json::is_valid_json_object(br#"{'a': 1}"#));
+    // nesting deeper than MAX_DEPTH
+    let depth = json::MAX_DEPTH as usize + 1;
+    let deep = format!(r#"{{"a": {}1{}}}"#, "[".repeat(depth), "]".repeat(depth));
+    assert!(!json::is_valid_json_object(deep.as_bytes()));
+}
+
+#[test]
+fn test_schema_versioning() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Did::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            AUTHOR_PUBKEY,
+            AUTHOR_DID,
+            vec![]
+        ));
 
         assert_ok!(Schema::add(
             Origin::signed(VALIDATOR_ACCOUNT),
-            schema_hash,
-            "TestSchema".as_bytes().to_vec()
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+        let v1 = BlakeTwo256::hash(TEST_SCHEMA.as_bytes());
+
+        // only the author or a validator can publish a new version
+        assert_noop!(
+            Schema::publish_version(
+                Origin::signed(AUTHOR_ACCOUNT),
+                v1,
+                version(2, 0, 0),
+                None,
+                TEST_SCHEMA_V2.as_bytes().to_vec()
+            ),
+            Error::<Test>::NotSchemaAuthor
+        );
+        // version must increase
+        assert_noop!(
+            Schema::publish_version(
+                Origin::signed(VALIDATOR_ACCOUNT),
+                v1,
+                version(1, 0, 0),
+                None,
+                TEST_SCHEMA_V2.as_bytes().to_vec()
+            ),
+            Error::<Test>::InvalidVersion
+        );
+
+        assert_ok!(Schema::publish_version(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            v1,
+            version(2, 0, 0),
+            None,
+            TEST_SCHEMA_V2.as_bytes().to_vec()
         ));
+        let v2 = BlakeTwo256::hash(TEST_SCHEMA_V2.as_bytes());
 
+        // a patch of an older release is listed in version order
+        assert_ok!(Schema::publish_version(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            v1,
+            version(1, 0, 1),
+            None,
+            TEST_SCHEMA_V3.as_bytes().to_vec()
+        ));
+        let v1_0_1 = BlakeTwo256::hash(TEST_SCHEMA_V3.as_bytes());
+
+        assert_eq!(
+            Schema::schema_versions(TEST_SCHEMA_NAME.to_vec()),
+            vec![
+                (version(1, 0, 0), v1),
+                (version(1, 0, 1), v1_0_1),
+                (version(2, 0, 0), v2)
+            ]
+        );
+        assert_eq!(
+            Schema::schema_info(v2),
+            Some(SchemaMetadata {
+                name: TEST_SCHEMA_NAME.to_vec(),
+                version: version(2, 0, 0),
+                previous: Some(v1),
+                status: SchemaStatus::Active,
+            })
+        );
+    })
+}
+
+#[test]
+fn test_schema_status_lifecycle() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
         assert_ok!(Schema::add(
             Origin::signed(VALIDATOR_ACCOUNT),
-            schema_hash,
-            "TestSchema".as_bytes().to_vec()
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
         ));
+        let hash = BlakeTwo256::hash(TEST_SCHEMA.as_bytes());
+        assert_eq!(Schema::schema_status(&hash), Some(SchemaStatus::Active));
+
+        assert_noop!(
+            Schema::update_status(Origin::signed(NON_VALIDATOR_ACCOUNT), hash, SchemaStatus::Retired),
+            Error::<Test>::NotSchemaAuthor
+        );
+
+        assert_ok!(Schema::update_status(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            hash,
+            SchemaStatus::Deprecated
+        ));
+        assert!(!Schema::is_schema_retired(&hash));
+
+        assert_ok!(Schema::update_status(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            hash,
+            SchemaStatus::Retired
+        ));
+        assert!(Schema::is_schema_retired(&hash));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::schema(RawEvent::SchemaStatusUpdated(hash, SchemaStatus::Retired))
+        );
+
+        // status can't go back
+        assert_noop!(
+            Schema::update_status(Origin::signed(VALIDATOR_ACCOUNT), hash, SchemaStatus::Active),
+            Error::<Test>::InvalidStatusTransition
+        );
+    })
+}
+
+#[test]
+fn test_schema_queries() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Schema::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+        let v1 = BlakeTwo256::hash(TEST_SCHEMA.as_bytes());
+        assert_ok!(Schema::publish_version(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            v1,
+            version(2, 0, 0),
+            None,
+            TEST_SCHEMA_V2.as_bytes().to_vec()
+        ));
+        let v2 = BlakeTwo256::hash(TEST_SCHEMA_V2.as_bytes());
+        assert_ok!(Schema::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            b"Address".to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA_V3.as_bytes().to_vec()
+        ));
+        let address = BlakeTwo256::hash(TEST_SCHEMA_V3.as_bytes());
+
+        let details = Schema::get_schema_details(v1).unwrap();
+        assert_eq!(details.author, VALIDATOR_DID);
+        assert_eq!(details.json_data, TEST_SCHEMA.as_bytes().to_vec());
+        assert_eq!(details.metadata.unwrap().name, TEST_SCHEMA_NAME.to_vec());
+        assert_eq!(Schema::get_schema_details(H256::zero()), None);
+
+        let by_author: Vec<H256> = Schema::get_schemas_by_author(VALIDATOR_DID)
+            .into_iter()
+            .map(|details| details.hash)
+            .collect();
+        assert_eq!(by_author, vec![v1, v2, address]);
+        assert!(Schema::get_schemas_by_author(AUTHOR_DID).is_empty());
+
+        let by_name: Vec<H256> = Schema::search_schemas_by_name(b"Pers".to_vec())
+            .into_iter()
+            .map(|details| details.hash)
+            .collect();
+        assert_eq!(by_name, vec![v1, v2]);
+        assert_eq!(Schema::search_schemas_by_name(Vec::new()).len(), 3);
+        assert!(Schema::search_schemas_by_name(b"Unknown".to_vec()).is_empty());
+    })
+}
+
+#[test]
+fn test_schema_deposit() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Did::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            AUTHOR_PUBKEY,
+            AUTHOR_DID,
+            vec![]
+        ));
+
+        // any registered DID can author a schema
+        assert_ok!(Schema::add(
+            Origin::signed(AUTHOR_ACCOUNT),
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+        let hash = BlakeTwo256::hash(TEST_SCHEMA.as_bytes());
+        let deposit = DEPOSIT_BASE + DEPOSIT_PER_BYTE * TEST_SCHEMA.len() as u64;
+        assert_eq!(Balances::reserved_balance(AUTHOR_ACCOUNT), deposit);
+        assert_eq!(Schema::schema_deposits(hash), Some((AUTHOR_ACCOUNT, deposit)));
+
+        // only the author can remove it
+        assert_noop!(
+            Schema::remove(Origin::signed(VALIDATOR_ACCOUNT), hash),
+            Error::<Test>::NotSchemaAuthor
+        );
+        assert_ok!(Schema::remove(Origin::signed(AUTHOR_ACCOUNT), hash));
+        assert_eq!(Balances::reserved_balance(AUTHOR_ACCOUNT), 0);
+        assert_eq!(Balances::free_balance(AUTHOR_ACCOUNT), INITIAL_BALANCE);
+        assert_eq!(Schema::schema(hash), None);
+        assert_eq!(Schema::schema_info(hash), None);
+        assert!(!SchemaVersions::<Test>::contains_key(TEST_SCHEMA_NAME.to_vec()));
+        assert!(Schema::schemas_by_author(AUTHOR_DID).is_empty());
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::schema(RawEvent::SchemaRemoved(hash))
+        );
+
+        // the name can be registered again once removed
+        assert_ok!(Schema::add(
+            Origin::signed(AUTHOR_ACCOUNT),
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+    })
+}
+
+#[test]
+fn test_force_remove_slashes_deposit() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+        assert_ok!(Did::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            AUTHOR_PUBKEY,
+            AUTHOR_DID,
+            vec![]
+        ));
+        assert_ok!(Schema::add(
+            Origin::signed(AUTHOR_ACCOUNT),
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            None,
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+        let hash = BlakeTwo256::hash(TEST_SCHEMA.as_bytes());
+        let deposit = DEPOSIT_BASE + DEPOSIT_PER_BYTE * TEST_SCHEMA.len() as u64;
+
+        // only validators can force the removal
+        assert_noop!(
+            Schema::force_remove(Origin::signed(AUTHOR_ACCOUNT), hash),
+            Error::<Test>::NotAValidator
+        );
+
+        assert_ok!(Schema::force_remove(Origin::signed(VALIDATOR_ACCOUNT), hash));
+        assert_eq!(Balances::reserved_balance(AUTHOR_ACCOUNT), 0);
+        assert_eq!(Balances::free_balance(AUTHOR_ACCOUNT), INITIAL_BALANCE - deposit);
+        assert_eq!(Schema::schema(hash), None);
+        assert_eq!(Schema::schema_deposits(hash), None);
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::schema(RawEvent::SchemaForceRemoved(hash, deposit))
+        );
+    })
+}
+
+#[test]
+fn test_add_schema_by_uri() {
+    new_test_ext().execute_with(|| {
+        let uri = b"ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec();
+
+        assert_noop!(
+            Schema::add(
+                Origin::signed(VALIDATOR_ACCOUNT),
+                TEST_SCHEMA_NAME.to_vec(),
+                version(1, 0, 0),
+                Some(Vec::new()),
+                TEST_SCHEMA.as_bytes().to_vec()
+            ),
+            Error::<Test>::InvalidUri
+        );
+        // content is still validated before being stored off-chain
+        assert_noop!(
+            Schema::add(
+                Origin::signed(VALIDATOR_ACCOUNT),
+                TEST_SCHEMA_NAME.to_vec(),
+                version(1, 0, 0),
+                Some(uri.clone()),
+                "TestSchema".as_bytes().to_vec()
+            ),
+            Error::<Test>::InvalidJson
+        );
+
+        assert_ok!(Schema::add(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            TEST_SCHEMA_NAME.to_vec(),
+            version(1, 0, 0),
+            Some(uri.clone()),
+            TEST_SCHEMA.as_bytes().to_vec()
+        ));
+
+        // only the hash and the URI are kept in state, the deposit covers the URI
+        let hash = BlakeTwo256::hash(TEST_SCHEMA.as_bytes());
+        assert_eq!(Schema::schema(hash), Some((VALIDATOR_DID, Vec::new())));
+        assert_eq!(Schema::schema_uris(hash), Some(uri.clone()));
+        assert_eq!(
+            Balances::reserved_balance(VALIDATOR_ACCOUNT),
+            DEPOSIT_BASE + DEPOSIT_PER_BYTE * uri.len() as u64
+        );
+        assert_eq!(Schema::get_schema_details(hash).unwrap().uri, Some(uri));
+
+        assert_ok!(Schema::remove(Origin::signed(VALIDATOR_ACCOUNT), hash));
+        assert_eq!(Schema::schema_uris(hash), None);
     })
 }
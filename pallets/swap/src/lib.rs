@@ -0,0 +1,346 @@
+//! # Swap Module
+//!
+//! ## Overview
+//!
+//! The swap module lets two DIDs atomically exchange amounts of different `tokens` currencies,
+//! or a token against the native currency. The maker reserves the asset offered and names the
+//! counterparty along with the asset and amount expected in exchange. The counterparty accepts
+//! the swap until the deadline, both legs are then settled in the same call. Once the deadline
+//! has passed the maker can cancel the swap to release the reserved asset.
+//!
+//! Tokens can also be settled against other chains with hash time-locked contracts (HTLCs). The
+//! sender locks an amount of a token to a recipient under the SHA-256 hash of a secret, the
+//! recipient claims it by revealing the secret before the timelock, otherwise the sender gets a
+//! refund. Each HTLC is identified by its own index rather than by its hashlock, so an HTLC
+//! created by someone else under the same hashlock can't block the real one. The secret is
+//! published in the claim event, so the same secret can unlock the matching contract on the
+//! other chain.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `create_swap` - Reserve an asset and offer it to a DID in exchange for another asset.
+//! - `accept_swap` - Settle a swap offered to the caller.
+//! - `cancel_swap` - Release the asset of an expired swap back to its maker.
+//! - `create_htlc` - Lock tokens to a DID under a hashlock and timelock.
+//! - `claim_htlc` - Claim the tokens of an HTLC with the secret of its hashlock.
+//! - `refund_htlc` - Return the tokens of an expired HTLC to its sender.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use did::Did;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure,
+    traits::{Currency, ExistenceRequirement, Get, ReservableCurrency},
+    transactional, StorageMap, StorageValue,
+};
+use frame_system::ensure_signed;
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use sp_runtime::{traits::Zero, DispatchResult};
+use sp_std::prelude::*;
+use tokens::{CurrencyCode, TokenBalance};
+
+#[cfg(test)]
+mod tests;
+mod structs;
+pub use crate::structs::*;
+
+/// An index of a swap. Just a `u32`.
+pub type SwapIndex = u32;
+
+pub type BalanceOf<T> = <<T as tokens::Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// SHA-256 hash of the secret unlocking an HTLC
+pub type Hashlock = [u8; 32];
+
+/// An index of an HTLC. Just a `u32`.
+pub type HtlcIndex = u32;
+
+pub trait Config: frame_system::Config + tokens::Config {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+    /// Maximum length of the secret revealed to claim an HTLC
+    type MaxSecretLength: Get<u32>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as Swap {
+        /// Number of swaps that have been created.
+        SwapCount get(fn swap_count): SwapIndex;
+
+        /// Swaps waiting to be accepted or cancelled
+        pub Swaps get(fn swaps): map hasher(twox_64_concat) SwapIndex => Option<PendingSwap<BalanceOf<T>, T::BlockNumber>>;
+
+        /// Number of HTLCs that have been created.
+        HtlcCount get(fn htlc_count): HtlcIndex;
+
+        /// HTLCs waiting to be claimed or refunded
+        pub HashedLocks get(fn htlc): map hasher(twox_64_concat) HtlcIndex => Option<HashedLock<T::BlockNumber>>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T> where BlockNumber = <T as frame_system::Config>::BlockNumber {
+        /// Swap offered and the maker's asset reserved [index, maker, counterparty, deadline]
+        SwapCreated(SwapIndex, Did, Did, BlockNumber),
+        /// Swap accepted and both assets exchanged [index, maker, counterparty]
+        SwapCompleted(SwapIndex, Did, Did),
+        /// Expired swap cancelled and the maker's asset released [index, maker]
+        SwapCancelled(SwapIndex, Did),
+        /// Tokens locked in an HTLC [index, hashlock, sender, recipient, CurrencyCode, amount, timelock]
+        HtlcCreated(HtlcIndex, Hashlock, Did, Did, CurrencyCode, TokenBalance, BlockNumber),
+        /// HTLC claimed by its recipient, revealing the secret [index, recipient, secret]
+        HtlcClaimed(HtlcIndex, Did, Vec<u8>),
+        /// Expired HTLC refunded to its sender [index, sender]
+        HtlcRefunded(HtlcIndex, Did),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// Caller has no registered DID
+        DIDNotRegistered,
+        /// The counterparty DID is not registered
+        CounterpartyDIDNotRegistered,
+        /// A DID can't swap with itself
+        SwapWithSelf,
+        /// No token is registered with the currency code
+        TokenNotFound,
+        /// Both sides of the swap use the same currency
+        SameCurrency,
+        /// Amount of one of the assets is zero
+        ZeroAmount,
+        /// Deadline is not in the future
+        DeadlineInPast,
+        /// No swap exists with the index
+        SwapNotFound,
+        /// Only the counterparty can accept the swap
+        NotCounterparty,
+        /// Only the maker can cancel the swap
+        NotMaker,
+        /// The deadline of the swap has passed
+        SwapExpired,
+        /// The swap can only be cancelled after its deadline
+        SwapNotExpired,
+        /// No more swaps can be indexed
+        SwapCountOverflow,
+        /// No HTLC exists with the index
+        HtlcNotFound,
+        /// The secret does not hash to the hashlock of the HTLC
+        InvalidSecret,
+        /// Secret exceeds the maximum length
+        SecretTooLong,
+        /// Only the recipient can claim the HTLC
+        NotRecipient,
+        /// Only the sender can refund the HTLC
+        NotSender,
+        /// The timelock of the HTLC has passed
+        HtlcExpired,
+        /// The HTLC can only be refunded after its timelock
+        HtlcNotExpired,
+        /// No more HTLCs can be indexed
+        HtlcCountOverflow,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        const MaxSecretLength: u32 = T::MaxSecretLength::get();
+
+        fn deposit_event() = default;
+
+        /// Reserve `offer` and propose to exchange it for `want` with `counterparty`
+        ///
+        /// The counterparty can accept the swap up to and including the `deadline` block.
+        #[weight = 1]
+        pub fn create_swap(
+            origin,
+            counterparty: Did,
+            offer: SwapAsset<BalanceOf<T>>,
+            want: SwapAsset<BalanceOf<T>>,
+            deadline: T::BlockNumber,
+        ) {
+            let sender = ensure_signed(origin)?;
+            ensure!(did::Module::<T>::does_did_exist(&sender), Error::<T>::DIDNotRegistered);
+            let maker = did::Module::<T>::get_did_from_account_id(&sender);
+            ensure!(did::Module::<T>::did_registered(&counterparty), Error::<T>::CounterpartyDIDNotRegistered);
+            ensure!(maker != counterparty, Error::<T>::SwapWithSelf);
+
+            ensure!(!offer.is_zero() && !want.is_zero(), Error::<T>::ZeroAmount);
+            ensure!(!offer.same_currency(&want), Error::<T>::SameCurrency);
+            Self::ensure_token_exists(&offer)?;
+            Self::ensure_token_exists(&want)?;
+            ensure!(deadline > <frame_system::Module<T>>::block_number(), Error::<T>::DeadlineInPast);
+            let index = Self::swap_count();
+            let next_index = index.checked_add(1).ok_or(Error::<T>::SwapCountOverflow)?;
+
+            Self::reserve(&sender, &offer)?;
+            SwapCount::put(next_index);
+            Swaps::<T>::insert(index, PendingSwap {
+                maker,
+                offer,
+                counterparty,
+                want,
+                deadline,
+            });
+
+            Self::deposit_event(RawEvent::SwapCreated(index, maker, counterparty, deadline));
+        }
+
+        /// Accept a swap offered to the caller, exchanging both assets
+        #[weight = 1]
+        #[transactional]
+        pub fn accept_swap(origin, index: SwapIndex) {
+            let sender = ensure_signed(origin)?;
+            let swap = Self::swaps(index).ok_or(Error::<T>::SwapNotFound)?;
+            let taker = did::Module::<T>::get_did_from_account_id(&sender);
+            ensure!(taker == swap.counterparty, Error::<T>::NotCounterparty);
+            ensure!(<frame_system::Module<T>>::block_number() <= swap.deadline, Error::<T>::SwapExpired);
+
+            let maker_account = did::Module::<T>::get_accountid_from_did(&swap.maker)?;
+            Self::unreserve(&maker_account, &swap.offer);
+            Self::transfer(&maker_account, &sender, &swap.offer)?;
+            Self::transfer(&sender, &maker_account, &swap.want)?;
+            Swaps::<T>::remove(index);
+
+            Self::deposit_event(RawEvent::SwapCompleted(index, swap.maker, taker));
+        }
+
+        /// Cancel an expired swap, releasing the asset reserved by the maker
+        #[weight = 1]
+        pub fn cancel_swap(origin, index: SwapIndex) {
+            let sender = ensure_signed(origin)?;
+            let swap = Self::swaps(index).ok_or(Error::<T>::SwapNotFound)?;
+            ensure!(did::Module::<T>::get_did_from_account_id(&sender) == swap.maker, Error::<T>::NotMaker);
+            ensure!(<frame_system::Module<T>>::block_number() > swap.deadline, Error::<T>::SwapNotExpired);
+
+            Self::unreserve(&sender, &swap.offer);
+            Swaps::<T>::remove(index);
+
+            Self::deposit_event(RawEvent::SwapCancelled(index, swap.maker));
+        }
+
+        /// Lock `amount` of a token to `recipient` until the `timelock` block
+        ///
+        /// `hashlock` is the SHA-256 hash of a secret known to the sender. The HTLC is identified by
+        /// the index published in the `HtlcCreated` event.
+        #[weight = 1]
+        pub fn create_htlc(
+            origin,
+            recipient: Did,
+            currency_code: CurrencyCode,
+            amount: TokenBalance,
+            hashlock: Hashlock,
+            timelock: T::BlockNumber,
+        ) {
+            let sender = ensure_signed(origin)?;
+            ensure!(did::Module::<T>::does_did_exist(&sender), Error::<T>::DIDNotRegistered);
+            let sender_did = did::Module::<T>::get_did_from_account_id(&sender);
+            ensure!(did::Module::<T>::did_registered(&recipient), Error::<T>::CounterpartyDIDNotRegistered);
+            ensure!(sender_did != recipient, Error::<T>::SwapWithSelf);
+
+            let asset = SwapAsset::Token(currency_code, amount);
+            ensure!(!asset.is_zero(), Error::<T>::ZeroAmount);
+            Self::ensure_token_exists(&asset)?;
+            ensure!(timelock > <frame_system::Module<T>>::block_number(), Error::<T>::DeadlineInPast);
+            let index = Self::htlc_count();
+            let next_index = index.checked_add(1).ok_or(Error::<T>::HtlcCountOverflow)?;
+
+            Self::reserve(&sender, &asset)?;
+            HtlcCount::put(next_index);
+            HashedLocks::<T>::insert(index, HashedLock {
+                sender: sender_did,
+                recipient,
+                currency_code,
+                amount,
+                hashlock,
+                timelock,
+            });
+
+            Self::deposit_event(RawEvent::HtlcCreated(index, hashlock, sender_did, recipient, currency_code, amount, timelock));
+        }
+
+        /// Claim the tokens of an HTLC locked to the caller by revealing the secret of its hashlock
+        ///
+        /// The HTLC can be claimed up to and including its timelock block.
+        #[weight = 1]
+        #[transactional]
+        pub fn claim_htlc(origin, index: HtlcIndex, secret: Vec<u8>) {
+            let sender = ensure_signed(origin)?;
+            ensure!(secret.len() <= T::MaxSecretLength::get() as usize, Error::<T>::SecretTooLong);
+            let htlc = Self::htlc(index).ok_or(Error::<T>::HtlcNotFound)?;
+            let recipient = did::Module::<T>::get_did_from_account_id(&sender);
+            ensure!(recipient == htlc.recipient, Error::<T>::NotRecipient);
+            ensure!(sp_io::hashing::sha2_256(&secret) == htlc.hashlock, Error::<T>::InvalidSecret);
+            ensure!(<frame_system::Module<T>>::block_number() <= htlc.timelock, Error::<T>::HtlcExpired);
+
+            let asset = SwapAsset::Token(htlc.currency_code, htlc.amount);
+            let sender_account = did::Module::<T>::get_accountid_from_did(&htlc.sender)?;
+            Self::unreserve(&sender_account, &asset);
+            Self::transfer(&sender_account, &sender, &asset)?;
+            HashedLocks::<T>::remove(index);
+
+            Self::deposit_event(RawEvent::HtlcClaimed(index, recipient, secret));
+        }
+
+        /// Return the tokens of an HTLC to its sender once the timelock has passed
+        #[weight = 1]
+        pub fn refund_htlc(origin, index: HtlcIndex) {
+            let sender = ensure_signed(origin)?;
+            let htlc = Self::htlc(index).ok_or(Error::<T>::HtlcNotFound)?;
+            ensure!(did::Module::<T>::get_did_from_account_id(&sender) == htlc.sender, Error::<T>::NotSender);
+            ensure!(<frame_system::Module<T>>::block_number() > htlc.timelock, Error::<T>::HtlcNotExpired);
+
+            Self::unreserve(&sender, &SwapAsset::Token(htlc.currency_code, htlc.amount));
+            HashedLocks::<T>::remove(index);
+
+            Self::deposit_event(RawEvent::HtlcRefunded(index, htlc.sender));
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    fn ensure_token_exists(asset: &SwapAsset<BalanceOf<T>>) -> DispatchResult {
+        if let SwapAsset::Token(currency_code, _) = asset {
+            ensure!(tokens::TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+        }
+        Ok(())
+    }
+
+    fn reserve(who: &T::AccountId, asset: &SwapAsset<BalanceOf<T>>) -> DispatchResult {
+        match asset {
+            SwapAsset::Native(amount) => T::Currency::reserve(who, *amount),
+            SwapAsset::Token(currency_code, amount) => {
+                let currency_id = tokens::Module::<T>::token_info(currency_code);
+                <tokens::Module<T> as MultiReservableCurrency<T::AccountId>>::reserve(currency_id, who, *amount)
+            }
+        }
+    }
+
+    fn unreserve(who: &T::AccountId, asset: &SwapAsset<BalanceOf<T>>) {
+        match asset {
+            SwapAsset::Native(amount) => {
+                T::Currency::unreserve(who, *amount);
+            }
+            SwapAsset::Token(currency_code, amount) => {
+                let currency_id = tokens::Module::<T>::token_info(currency_code);
+                <tokens::Module<T> as MultiReservableCurrency<T::AccountId>>::unreserve(currency_id, who, *amount);
+            }
+        }
+    }
+
+    fn transfer(from: &T::AccountId, to: &T::AccountId, asset: &SwapAsset<BalanceOf<T>>) -> DispatchResult {
+        match asset {
+            SwapAsset::Native(amount) => {
+                T::Currency::transfer(from, to, *amount, ExistenceRequirement::AllowDeath)
+            }
+            SwapAsset::Token(currency_code, amount) => {
+                let currency_id = tokens::Module::<T>::token_info(currency_code);
+                <tokens::Module<T> as MultiCurrency<T::AccountId>>::transfer(currency_id, from, to, *amount)
+            }
+        }
+    }
+}
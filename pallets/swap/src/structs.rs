@@ -0,0 +1,68 @@
+use super::*;
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+
+/// An asset and amount taking part in a swap
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwapAsset<Balance> {
+    /// Native currency of the chain
+    Native(Balance),
+    /// Token of the tokens module
+    Token(CurrencyCode, TokenBalance),
+}
+
+impl<Balance: Zero> SwapAsset<Balance> {
+    /// Whether the amount of the asset is zero
+    pub fn is_zero(&self) -> bool {
+        match self {
+            SwapAsset::Native(amount) => amount.is_zero(),
+            SwapAsset::Token(_, amount) => amount.is_zero(),
+        }
+    }
+
+    /// Whether both assets are the same currency, regardless of the amounts
+    pub fn same_currency(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SwapAsset::Native(_), SwapAsset::Native(_)) => true,
+            (SwapAsset::Token(code, _), SwapAsset::Token(other_code, _)) => code == other_code,
+            _ => false,
+        }
+    }
+}
+
+/// A swap offered by `maker` to `counterparty`, the `offer` is held in reserve until the swap is
+/// accepted or cancelled
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PendingSwap<Balance, BlockNumber> {
+    /// DID that created the swap and reserved the offer
+    pub maker: Did,
+    /// Asset the maker gives away
+    pub offer: SwapAsset<Balance>,
+    /// Only DID allowed to accept the swap
+    pub counterparty: Did,
+    /// Asset the maker gets in exchange
+    pub want: SwapAsset<Balance>,
+    /// Last block at which the swap can be accepted, the maker can cancel it afterwards
+    pub deadline: BlockNumber,
+}
+
+/// Tokens locked by `sender` that `recipient` can claim with the secret of the hashlock until
+/// `timelock`, and that `sender` can refund afterwards
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HashedLock<BlockNumber> {
+    /// DID that locked the tokens
+    pub sender: Did,
+    /// DID that can claim the tokens
+    pub recipient: Did,
+    /// Token locked
+    pub currency_code: CurrencyCode,
+    /// Amount locked, held in reserve by the sender
+    pub amount: TokenBalance,
+    /// SHA-256 hash of the secret unlocking the tokens
+    pub hashlock: Hashlock,
+    /// Last block at which the HTLC can be claimed
+    pub timelock: BlockNumber,
+}
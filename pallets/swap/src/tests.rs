@@ -0,0 +1,521 @@
+// Tests for swap module
+use super::*;
+use crate as swap;
+use frame_support::{
+    assert_noop, assert_ok, ord_parameter_types, parameter_types, traits::StorageMapShim,
+};
+use frame_system::EnsureSignedBy;
+use sp_core::{sr25519, H256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Module, Call, Config, Storage, Event<T>},
+        Did: did::{Module, Call, Storage, Event, Config},
+        ValidatorSet: validator_set::{Module, Call, Storage, Event, Config},
+        Balances: balances::{Module, Call, Storage, Event<T>, Config<T>},
+        VC: vc::{Module, Call, Storage, Event},
+        Tokens: tokens::{Module, Call, Storage, Event},
+        Swap: swap::{Module, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<u64>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+}
+
+impl did::Config for Test {
+    type Event = Event;
+}
+
+impl validator_set::Config for Test {
+    type Event = Event;
+    type ApproveOrigin = frame_system::EnsureRoot<u64>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = StorageMapShim<
+        balances::Account<Test>,
+        frame_system::Provider<Test>,
+        u64,
+        balances::AccountData<u64>,
+    >;
+    type MaxLocks = MaxLocks;
+    type WeightInfo = ();
+    type DidResolution = did::Module<Test>;
+}
+
+parameter_types! {
+    pub const VCDeposit: u64 = 100;
+    pub const VCTombstoneDeposit: u64 = 10;
+    pub const VCMaxAge: u64 = 100;
+}
+
+ord_parameter_types! {
+    pub const CouncilElectedUser: u64 = MAKER_ACCOUNT;
+}
+
+impl vc::Config for Test {
+    type Event = Event;
+    type ApproveOrigin = EnsureSignedBy<CouncilElectedUser, u64>;
+    type Currency = Balances;
+    type VCDeposit = VCDeposit;
+    type VCTombstoneDeposit = VCTombstoneDeposit;
+    type VCMaxAge = VCMaxAge;
+}
+
+parameter_types! {
+    pub const TreasuryReserveAmount: u64 = 1_000;
+    pub const MaxBatchTransfers: u32 = 3;
+    pub const MaxTokenMetadataLength: u32 = 32;
+}
+
+impl tokens::Config for Test {
+    type Event = Event;
+    type Amount = i64;
+    type CurrencyId = u32;
+    type WeightInfo = ();
+    type Currency = Balances;
+    type TreasuryReserve = TreasuryReserveAmount;
+    type VcVerifier = VC;
+    type MaxLocks = MaxLocks;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type MaxTokenMetadataLength = MaxTokenMetadataLength;
+    type TransferPolicy = tokens::RestrictionPolicy<Test>;
+}
+
+parameter_types! {
+    pub const MaxSecretLength: u32 = 32;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type MaxSecretLength = MaxSecretLength;
+}
+
+const MAKER_ACCOUNT: u64 = 72340172838076673;
+const MAKER_DID: did::Did = *b"did:ssid:maker\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const MAKER_PUBKEY: sr25519::Public = sr25519::Public([1; 32]);
+const TAKER_ACCOUNT: u64 = 144680345676153346;
+const TAKER_DID: did::Did = *b"did:ssid:taker\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const TAKER_PUBKEY: sr25519::Public = sr25519::Public([2; 32]);
+const OTHER_ACCOUNT: u64 = 217020518514230019;
+const OTHER_DID: did::Did = *b"did:ssid:other\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const OTHER_PUBKEY: sr25519::Public = sr25519::Public([3; 32]);
+const UNKNOWN_DID: did::Did = *b"did:ssid:unknown\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const MAKER_CURRENCY_ID: u32 = 1;
+const MAKER_CURRENCY: CurrencyCode = *b"MKR\0\0\0\0\0";
+const TAKER_CURRENCY_ID: u32 = 2;
+const TAKER_CURRENCY: CurrencyCode = *b"TKR\0\0\0\0\0";
+const ISSUED_AMOUNT: TokenBalance = 10_000;
+const INITIAL_BALANCE: u64 = 1_000;
+
+// This function basically just builds a genesis storage key/value store according to
+// our desired mockup.
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut o = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    did::GenesisConfig {
+        dids: vec![
+            did::DidStruct {
+                identifier: MAKER_DID,
+                public_key: MAKER_PUBKEY,
+                metadata: vec![],
+            },
+            did::DidStruct {
+                identifier: TAKER_DID,
+                public_key: TAKER_PUBKEY,
+                metadata: vec![],
+            },
+            did::DidStruct {
+                identifier: OTHER_DID,
+                public_key: OTHER_PUBKEY,
+                metadata: vec![],
+            },
+        ],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    balances::GenesisConfig::<Test> {
+        balances: vec![(MAKER_ACCOUNT, INITIAL_BALANCE), (TAKER_ACCOUNT, INITIAL_BALANCE)],
+    }
+    .assimilate_storage(&mut o)
+    .unwrap();
+
+    let mut ext: sp_io::TestExternalities = o.into();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        // register the tokens without going through a TokenVC
+        for &(currency_id, currency_code, issuer, account) in &[
+            (MAKER_CURRENCY_ID, MAKER_CURRENCY, MAKER_DID, MAKER_ACCOUNT),
+            (TAKER_CURRENCY_ID, TAKER_CURRENCY, TAKER_DID, TAKER_ACCOUNT),
+        ] {
+            tokens::Module::<Test>::set_token_info(currency_id, currency_code);
+            tokens::TokenData::insert(currency_code, tokens::TokenDetails::default());
+            tokens::TokenIssuer::insert(currency_code, issuer);
+            assert_ok!(<Tokens as MultiCurrency<u64>>::deposit(
+                currency_id,
+                &account,
+                ISSUED_AMOUNT
+            ));
+        }
+    });
+    ext
+}
+
+fn free_balance(currency_id: u32, who: u64) -> TokenBalance {
+    <Tokens as MultiCurrency<u64>>::free_balance(currency_id, &who)
+}
+
+fn reserved_balance(currency_id: u32, who: u64) -> TokenBalance {
+    <Tokens as MultiReservableCurrency<u64>>::reserved_balance(currency_id, &who)
+}
+
+#[test]
+fn test_token_swap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Swap::create_swap(
+            Origin::signed(MAKER_ACCOUNT),
+            TAKER_DID,
+            SwapAsset::Token(MAKER_CURRENCY, 100),
+            SwapAsset::Token(TAKER_CURRENCY, 300),
+            10
+        ));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::swap(RawEvent::SwapCreated(0, MAKER_DID, TAKER_DID, 10))
+        );
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), ISSUED_AMOUNT - 100);
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 100);
+
+        assert_noop!(
+            Swap::accept_swap(Origin::signed(OTHER_ACCOUNT), 0),
+            Error::<Test>::NotCounterparty
+        );
+        assert_ok!(Swap::accept_swap(Origin::signed(TAKER_ACCOUNT), 0));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::swap(RawEvent::SwapCompleted(0, MAKER_DID, TAKER_DID))
+        );
+        assert_eq!(Swap::swaps(0), None);
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 0);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), ISSUED_AMOUNT - 100);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, TAKER_ACCOUNT), 100);
+        assert_eq!(free_balance(TAKER_CURRENCY_ID, TAKER_ACCOUNT), ISSUED_AMOUNT - 300);
+        assert_eq!(free_balance(TAKER_CURRENCY_ID, MAKER_ACCOUNT), 300);
+    });
+}
+
+#[test]
+fn test_native_swap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Swap::create_swap(
+            Origin::signed(MAKER_ACCOUNT),
+            TAKER_DID,
+            SwapAsset::Native(400),
+            SwapAsset::Token(TAKER_CURRENCY, 50),
+            10
+        ));
+        assert_eq!(Balances::reserved_balance(MAKER_ACCOUNT), 400);
+
+        assert_ok!(Swap::accept_swap(Origin::signed(TAKER_ACCOUNT), 0));
+        assert_eq!(Balances::reserved_balance(MAKER_ACCOUNT), 0);
+        assert_eq!(Balances::free_balance(MAKER_ACCOUNT), INITIAL_BALANCE - 400);
+        assert_eq!(Balances::free_balance(TAKER_ACCOUNT), INITIAL_BALANCE + 400);
+        assert_eq!(free_balance(TAKER_CURRENCY_ID, MAKER_ACCOUNT), 50);
+    });
+}
+
+#[test]
+fn test_failed_accept_keeps_swap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Swap::create_swap(
+            Origin::signed(MAKER_ACCOUNT),
+            TAKER_DID,
+            SwapAsset::Token(MAKER_CURRENCY, 100),
+            SwapAsset::Native(INITIAL_BALANCE + 1),
+            10
+        ));
+
+        // the taker can't pay, nothing is settled
+        assert!(Swap::accept_swap(Origin::signed(TAKER_ACCOUNT), 0).is_err());
+        assert!(Swap::swaps(0).is_some());
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 100);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, TAKER_ACCOUNT), 0);
+    });
+}
+
+#[test]
+fn test_cancel_swap() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Swap::create_swap(
+            Origin::signed(MAKER_ACCOUNT),
+            TAKER_DID,
+            SwapAsset::Token(MAKER_CURRENCY, 100),
+            SwapAsset::Native(10),
+            10
+        ));
+
+        assert_noop!(
+            Swap::cancel_swap(Origin::signed(MAKER_ACCOUNT), 0),
+            Error::<Test>::SwapNotExpired
+        );
+
+        System::set_block_number(11);
+        assert_noop!(
+            Swap::accept_swap(Origin::signed(TAKER_ACCOUNT), 0),
+            Error::<Test>::SwapExpired
+        );
+        assert_noop!(
+            Swap::cancel_swap(Origin::signed(TAKER_ACCOUNT), 0),
+            Error::<Test>::NotMaker
+        );
+        assert_ok!(Swap::cancel_swap(Origin::signed(MAKER_ACCOUNT), 0));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::swap(RawEvent::SwapCancelled(0, MAKER_DID))
+        );
+        assert_eq!(Swap::swaps(0), None);
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 0);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), ISSUED_AMOUNT);
+        assert_noop!(
+            Swap::cancel_swap(Origin::signed(MAKER_ACCOUNT), 0),
+            Error::<Test>::SwapNotFound
+        );
+    });
+}
+
+#[test]
+fn test_create_swap_validation() {
+    new_test_ext().execute_with(|| {
+        let create = |counterparty: did::Did, offer: SwapAsset<u64>, want: SwapAsset<u64>, deadline: u64| {
+            Swap::create_swap(Origin::signed(MAKER_ACCOUNT), counterparty, offer, want, deadline)
+        };
+        let offer = SwapAsset::Token(MAKER_CURRENCY, 100);
+        let want = SwapAsset::Native(10);
+
+        assert_noop!(
+            Swap::create_swap(Origin::signed(1), TAKER_DID, offer, want, 10),
+            Error::<Test>::DIDNotRegistered
+        );
+        assert_noop!(
+            create(UNKNOWN_DID, offer, want, 10),
+            Error::<Test>::CounterpartyDIDNotRegistered
+        );
+        assert_noop!(create(MAKER_DID, offer, want, 10), Error::<Test>::SwapWithSelf);
+        assert_noop!(
+            create(TAKER_DID, offer, SwapAsset::Native(0), 10),
+            Error::<Test>::ZeroAmount
+        );
+        assert_noop!(
+            create(TAKER_DID, offer, SwapAsset::Token(MAKER_CURRENCY, 10), 10),
+            Error::<Test>::SameCurrency
+        );
+        assert_noop!(
+            create(TAKER_DID, offer, SwapAsset::Token(*b"NONE\0\0\0\0", 10), 10),
+            Error::<Test>::TokenNotFound
+        );
+        assert_noop!(create(TAKER_DID, offer, want, 1), Error::<Test>::DeadlineInPast);
+        assert_noop!(
+            create(TAKER_DID, SwapAsset::Token(MAKER_CURRENCY, ISSUED_AMOUNT + 1), want, 10),
+            tokens::Error::<Test>::BalanceTooLow
+        );
+
+        SwapCount::put(SwapIndex::MAX);
+        assert_noop!(create(TAKER_DID, offer, want, 10), Error::<Test>::SwapCountOverflow);
+    });
+}
+
+#[test]
+fn test_htlc_claim() {
+    new_test_ext().execute_with(|| {
+        let secret = b"settlement secret".to_vec();
+        let hashlock = sp_io::hashing::sha2_256(&secret);
+        assert_ok!(Swap::create_htlc(
+            Origin::signed(MAKER_ACCOUNT),
+            TAKER_DID,
+            MAKER_CURRENCY,
+            100,
+            hashlock,
+            10
+        ));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::swap(RawEvent::HtlcCreated(0, hashlock, MAKER_DID, TAKER_DID, MAKER_CURRENCY, 100, 10))
+        );
+        assert_eq!(Swap::htlc_count(), 1);
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 100);
+
+        assert_noop!(
+            Swap::claim_htlc(Origin::signed(TAKER_ACCOUNT), 1, secret.clone()),
+            Error::<Test>::HtlcNotFound
+        );
+        assert_noop!(
+            Swap::claim_htlc(Origin::signed(TAKER_ACCOUNT), 0, b"wrong secret".to_vec()),
+            Error::<Test>::InvalidSecret
+        );
+        assert_noop!(
+            Swap::claim_htlc(Origin::signed(TAKER_ACCOUNT), 0, vec![0; 33]),
+            Error::<Test>::SecretTooLong
+        );
+        assert_noop!(
+            Swap::claim_htlc(Origin::signed(OTHER_ACCOUNT), 0, secret.clone()),
+            Error::<Test>::NotRecipient
+        );
+        assert_noop!(
+            Swap::refund_htlc(Origin::signed(MAKER_ACCOUNT), 0),
+            Error::<Test>::HtlcNotExpired
+        );
+
+        System::set_block_number(10);
+        assert_ok!(Swap::claim_htlc(Origin::signed(TAKER_ACCOUNT), 0, secret.clone()));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::swap(RawEvent::HtlcClaimed(0, TAKER_DID, secret))
+        );
+        assert_eq!(Swap::htlc(0), None);
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 0);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), ISSUED_AMOUNT - 100);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, TAKER_ACCOUNT), 100);
+    });
+}
+
+#[test]
+fn test_htlc_refund() {
+    new_test_ext().execute_with(|| {
+        let secret = b"settlement secret".to_vec();
+        let hashlock = sp_io::hashing::sha2_256(&secret);
+        assert_noop!(
+            Swap::create_htlc(Origin::signed(MAKER_ACCOUNT), TAKER_DID, MAKER_CURRENCY, 0, hashlock, 10),
+            Error::<Test>::ZeroAmount
+        );
+        assert_noop!(
+            Swap::create_htlc(Origin::signed(MAKER_ACCOUNT), TAKER_DID, MAKER_CURRENCY, 100, hashlock, 1),
+            Error::<Test>::DeadlineInPast
+        );
+        HtlcCount::put(HtlcIndex::MAX);
+        assert_noop!(
+            Swap::create_htlc(Origin::signed(MAKER_ACCOUNT), TAKER_DID, MAKER_CURRENCY, 100, hashlock, 10),
+            Error::<Test>::HtlcCountOverflow
+        );
+        HtlcCount::put(0);
+        assert_ok!(Swap::create_htlc(
+            Origin::signed(MAKER_ACCOUNT),
+            TAKER_DID,
+            MAKER_CURRENCY,
+            100,
+            hashlock,
+            10
+        ));
+
+        System::set_block_number(11);
+        assert_noop!(
+            Swap::claim_htlc(Origin::signed(TAKER_ACCOUNT), 0, secret),
+            Error::<Test>::HtlcExpired
+        );
+        assert_noop!(
+            Swap::refund_htlc(Origin::signed(TAKER_ACCOUNT), 0),
+            Error::<Test>::NotSender
+        );
+        assert_ok!(Swap::refund_htlc(Origin::signed(MAKER_ACCOUNT), 0));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::swap(RawEvent::HtlcRefunded(0, MAKER_DID))
+        );
+        assert_eq!(Swap::htlc(0), None);
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 0);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), ISSUED_AMOUNT);
+    });
+}
+
+#[test]
+fn test_htlc_with_front_run_hashlock() {
+    new_test_ext().execute_with(|| {
+        let secret = b"settlement secret".to_vec();
+        let hashlock = sp_io::hashing::sha2_256(&secret);
+
+        // someone seeing the hashlock locks a dust amount under it first
+        assert_ok!(<Tokens as MultiCurrency<u64>>::deposit(MAKER_CURRENCY_ID, &OTHER_ACCOUNT, 10));
+        assert_ok!(Swap::create_htlc(
+            Origin::signed(OTHER_ACCOUNT),
+            TAKER_DID,
+            MAKER_CURRENCY,
+            1,
+            hashlock,
+            2
+        ));
+
+        // the real HTLC is still created under its own index
+        assert_ok!(Swap::create_htlc(
+            Origin::signed(MAKER_ACCOUNT),
+            TAKER_DID,
+            MAKER_CURRENCY,
+            100,
+            hashlock,
+            10
+        ));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::swap(RawEvent::HtlcCreated(1, hashlock, MAKER_DID, TAKER_DID, MAKER_CURRENCY, 100, 10))
+        );
+
+        assert_ok!(Swap::claim_htlc(Origin::signed(TAKER_ACCOUNT), 1, secret));
+        assert_eq!(Swap::htlc(1), None);
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, TAKER_ACCOUNT), 100);
+        assert_eq!(reserved_balance(MAKER_CURRENCY_ID, MAKER_ACCOUNT), 0);
+
+        // the front-running HTLC is left for its sender to refund
+        System::set_block_number(3);
+        assert_ok!(Swap::refund_htlc(Origin::signed(OTHER_ACCOUNT), 0));
+        assert_eq!(free_balance(MAKER_CURRENCY_ID, OTHER_ACCOUNT), 10);
+    });
+}
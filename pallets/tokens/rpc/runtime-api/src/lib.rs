@@ -0,0 +1,21 @@
+//! Runtime API definition for the Tokens pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+use did::Did;
+use sp_std::prelude::*;
+use tokens::{CurrencyCode, MintableSupply, TokenBalance, TokenMetadataDetails};
+
+sp_api::decl_runtime_apis! {
+    pub trait TokensApi {
+        /// Get the details and display metadata of the token
+        fn token_metadata(currency_code: CurrencyCode) -> Option<TokenMetadataDetails>;
+
+        /// Get the amount `spender` may transfer out of the balance of `owner`
+        fn allowance(owner: Did, spender: Did, currency_code: CurrencyCode) -> TokenBalance;
+
+        /// Get the amount of the token that can still be minted under its supply cap and rate limit
+        fn mintable_supply(currency_code: CurrencyCode) -> Option<MintableSupply>;
+    }
+}
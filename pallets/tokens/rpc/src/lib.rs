@@ -0,0 +1,225 @@
+//! RPC interface for the Tokens pallet.
+
+use std::sync::Arc;
+
+use did::Did;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use tokens::{CurrencyCode, MintableSupply, TokenMetadataDetails};
+pub use tokens_rpc_runtime_api::TokensApi as TokensRuntimeApi;
+
+/// Convert bytes padded with trailing zeros to a string
+fn to_string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_string()
+}
+
+/// Display information of a token
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMetadataResponse {
+    pub currency_code: String,
+    pub name: String,
+    pub decimals: u8,
+    pub issuer: String,
+    pub logo_uri: String,
+    pub website: String,
+    pub description: String,
+}
+
+impl From<TokenMetadataDetails> for TokenMetadataResponse {
+    fn from(token: TokenMetadataDetails) -> Self {
+        let name = if token.metadata.display_name.is_empty() {
+            &token.details.token_name
+        } else {
+            &token.metadata.display_name
+        };
+        TokenMetadataResponse {
+            currency_code: to_string(&token.details.currency_code),
+            name: to_string(name),
+            decimals: token.details.decimal,
+            issuer: to_string(&token.issuer),
+            logo_uri: to_string(&token.metadata.logo_uri),
+            website: to_string(&token.metadata.website),
+            description: to_string(&token.metadata.description),
+        }
+    }
+}
+
+/// Amount of a token that can still be minted as a decimal string, `null` when unlimited
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MintableSupplyResponse {
+    pub remaining_supply: Option<String>,
+    pub remaining_in_period: Option<String>,
+}
+
+impl From<MintableSupply> for MintableSupplyResponse {
+    fn from(supply: MintableSupply) -> Self {
+        MintableSupplyResponse {
+            remaining_supply: supply.remaining_supply.map(|amount| amount.to_string()),
+            remaining_in_period: supply.remaining_in_period.map(|amount| amount.to_string()),
+        }
+    }
+}
+
+#[rpc]
+pub trait TokensApi<BlockHash> {
+    /// Get the display metadata of the token with the given currency code
+    #[rpc(name = "tokens_metadata")]
+    fn metadata(
+        &self,
+        currency_code: String,
+        at: Option<BlockHash>,
+    ) -> Result<Option<TokenMetadataResponse>>;
+
+    /// Get the amount `spender` may transfer out of the balance of `owner`
+    ///
+    /// The amount is returned as a decimal string, JSON numbers can't hold every `TokenBalance`
+    #[rpc(name = "tokens_allowance")]
+    fn allowance(
+        &self,
+        owner: String,
+        spender: String,
+        currency_code: String,
+        at: Option<BlockHash>,
+    ) -> Result<String>;
+
+    /// Get the amount of the token that can still be minted under its supply cap and rate limit
+    #[rpc(name = "tokens_mintableSupply")]
+    fn mintable_supply(
+        &self,
+        currency_code: String,
+        at: Option<BlockHash>,
+    ) -> Result<Option<MintableSupplyResponse>>;
+}
+
+/// A struct that implements the `TokensApi`.
+pub struct Tokens<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Tokens<C, B> {
+    /// Create new `Tokens` instance with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The call to runtime failed.
+    RuntimeError,
+    /// The currency code is longer than 8 bytes.
+    InvalidCurrencyCode,
+    /// The DID is longer than 32 bytes.
+    InvalidDid,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::RuntimeError => 1,
+            Error::InvalidCurrencyCode => 2,
+            Error::InvalidDid => 3,
+        }
+    }
+}
+
+fn runtime_error<E: std::fmt::Debug>(message: &str, e: E) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(Error::RuntimeError.into()),
+        message: message.into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+/// Pad the given string with zeros to a fixed size identifier
+fn to_padded<const N: usize>(value: &str, error: Error, message: &str) -> Result<[u8; N]> {
+    let mut padded = [0; N];
+    if value.len() > N {
+        return Err(RpcError {
+            code: ErrorCode::ServerError(error.into()),
+            message: message.into(),
+            data: None,
+        });
+    }
+    padded[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(padded)
+}
+
+/// Parse a currency code given as a string, padding it with zeros
+fn parse_currency_code(currency_code: &str) -> Result<CurrencyCode> {
+    to_padded(
+        currency_code,
+        Error::InvalidCurrencyCode,
+        "Currency code is longer than 8 bytes.",
+    )
+}
+
+/// Parse a DID given as a string, padding it with zeros
+fn parse_did(did: &str) -> Result<Did> {
+    to_padded(did, Error::InvalidDid, "DID is longer than 32 bytes.")
+}
+
+impl<C, Block> TokensApi<<Block as BlockT>::Hash> for Tokens<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: TokensRuntimeApi<Block>,
+{
+    fn metadata(
+        &self,
+        currency_code: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<TokenMetadataResponse>> {
+        let currency_code = parse_currency_code(&currency_code)?;
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.token_metadata(&at, currency_code)
+            .map(|token| token.map(Into::into))
+            .map_err(|e| runtime_error("Unable to query token metadata.", e))
+    }
+
+    fn allowance(
+        &self,
+        owner: String,
+        spender: String,
+        currency_code: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<String> {
+        let owner = parse_did(&owner)?;
+        let spender = parse_did(&spender)?;
+        let currency_code = parse_currency_code(&currency_code)?;
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.allowance(&at, owner, spender, currency_code)
+            .map(|allowance| allowance.to_string())
+            .map_err(|e| runtime_error("Unable to query allowance.", e))
+    }
+
+    fn mintable_supply(
+        &self,
+        currency_code: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<MintableSupplyResponse>> {
+        let currency_code = parse_currency_code(&currency_code)?;
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.mintable_supply(&at, currency_code)
+            .map(|supply| supply.map(Into::into))
+            .map_err(|e| runtime_error("Unable to query mintable supply.", e))
+    }
+}
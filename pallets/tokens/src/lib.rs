@@ -28,6 +28,18 @@
 //!
 //! - `transfer` - Transfer some balance to another account.
 //! - `transfer_all` - Transfer all balance to another account.
+//! - `pause`/`unpause` - Halt or resume all transfers of a token.
+//! - `freeze`/`thaw` - Prevent or allow a DID moving its balance of a token.
+//! - `approve` - Allow another DID to spend some of the caller's balance.
+//! - `increase_allowance`/`decrease_allowance` - Adjust an existing allowance.
+//! - `transfer_from` - Transfer balance of another DID within the caller's allowance.
+//! - `batch_transfer` - Transfer a token to many accounts, each with an optional memo.
+//! - `set_mint_rate_limit` - Limit the amount of a token minted in each period.
+//! - `burn` - Destroy some of the caller's balance.
+//! - `request_redemption` - Reserve balance for the issuer to redeem off-chain.
+//! - `confirm_redemption`/`reject_redemption` - Burn or release the balance of a redemption request.
+//! - `set_transfer_restriction` - Choose who can send and receive a token.
+//! - `add_to_whitelist`/`remove_from_whitelist` - Manage the DIDs allowed to hold a whitelisted token.
 //!
 //! ### Genesis Config
 //!
@@ -37,14 +49,16 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 use codec::{Decode, Encode};
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage, ensure, fail,
+    decl_error, decl_event, decl_module, decl_storage, ensure,
     traits::Get,
     traits::{
         BalanceStatus as Status, Currency as PalletCurrency, ExistenceRequirement, Imbalance,
-        ReservableCurrency as PalletReservableCurrency, SignedImbalance, WithdrawReasons,
+        LockableCurrency as PalletLockableCurrency, ReservableCurrency as PalletReservableCurrency,
+        SignedImbalance, WithdrawReasons,
     },
+    transactional,
     weights::Weight,
-    Parameter, StorageMap,
+    IterableStorageDoubleMap, IterableStorageMap, Parameter, StorageDoubleMap, StorageMap,
 };
 use frame_system::{ensure_root, ensure_signed};
 use num::traits::{FromPrimitive, ToPrimitive};
@@ -53,6 +67,7 @@ use sp_runtime::{
     DispatchError, DispatchResult, RuntimeDebug,
 };
 use sp_std::{
+    collections::btree_map::BTreeMap,
     convert::{TryFrom, TryInto},
     marker,
     prelude::*,
@@ -60,16 +75,21 @@ use sp_std::{
 };
 
 pub use crate::imbalances::{NegativeImbalance, PositiveImbalance};
+pub use crate::policy::{RestrictionPolicy, TransferPolicy};
 pub use crate::structs::*;
 pub type TokenBalance = u128;
+use balances::Memo;
 use did::Did;
+use vc::VcVerifier;
 use orml_traits::{
     arithmetic::{self, Signed},
-    BalanceStatus, LockIdentifier, MultiCurrency, MultiCurrencyExtended, MultiReservableCurrency,
+    BalanceStatus, LockIdentifier, MultiCurrency, MultiCurrencyExtended, MultiLockableCurrency,
+    MultiReservableCurrency,
 };
 pub type CurrencyCode = [u8; 8];
 
 mod migration;
+mod policy;
 mod structs;
 mod tests;
 
@@ -84,7 +104,7 @@ pub trait WeightInfo {
 type BalanceOf<T> =
     <<T as Config>::Currency as PalletCurrency<<T as frame_system::Config>::AccountId>>::Balance;
 
-pub trait Config: frame_system::Config + did::Config + vc::Config {
+pub trait Config: frame_system::Config + did::Config {
     type Event: From<Event> + Into<<Self as frame_system::Config>::Event>;
 
     /// The currency trait.
@@ -114,6 +134,23 @@ pub trait Config: frame_system::Config + did::Config + vc::Config {
     /// The treasury reserve type
     type TreasuryReserve: Get<BalanceOf<Self>>;
 
+    /// Verifier used to read and consume the VCs backing token operations
+    type VcVerifier: vc::VcVerifier<Self::Hash>;
+
+    /// The maximum number of locks that should exist on an account for a token.
+    /// Not strictly enforced, but used for weight estimation.
+    type MaxLocks: Get<u32>;
+
+    /// Maximum number of transfers in a `batch_transfer`
+    type MaxBatchTransfers: Get<u32>;
+
+    /// Maximum length of each field of the token metadata
+    type MaxTokenMetadataLength: Get<u32>;
+
+    /// Compliance check run on every transfer, `RestrictionPolicy` enforces the restriction set
+    /// by each token owner
+    type TransferPolicy: TransferPolicy;
+
     /// Weight information for extrinsics in this module.
     type WeightInfo: WeightInfo;
 }
@@ -123,9 +160,9 @@ decl_storage! {
         /// The total issuance of a token type.
         pub TotalIssuance get(fn total_issuance): map hasher(twox_64_concat) CurrencyCode => TokenBalance;
 
-        /// Any liquidity locks of a token type under an account.
+        /// Any liquidity locks of a token type under a did, so they follow the did across key rotations.
         /// NOTE: Should only be accessed when setting, changing and freeing a lock.
-        pub Locks get(fn locks): double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) CurrencyCode => Vec<BalanceLock<TokenBalance>>;
+        pub Locks get(fn locks): double_map hasher(blake2_128_concat) Did, hasher(twox_64_concat) CurrencyCode => Vec<BalanceLock<TokenBalance>>;
 
         /// The balance of a token type under an account.
         ///
@@ -135,6 +172,26 @@ decl_storage! {
         pub Accounts get(fn accounts): double_map hasher(blake2_128_concat) did::Did, hasher(twox_64_concat) CurrencyCode => TokenAccountInfo<T::Index ,TokenAccountData>;
         /// map to store a friendsly name for token
         pub TokenData get(fn token_data) : map hasher(blake2_128_concat) CurrencyCode => Option<TokenDetails>;
+        /// Display metadata of a token, set by its owner
+        pub TokenMetadataOf get(fn token_metadata): map hasher(blake2_128_concat) CurrencyCode => Option<TokenMetadata>;
+        /// Tokens whose transfers are halted by the owner or root
+        pub PausedTokens get(fn is_paused): map hasher(blake2_128_concat) CurrencyCode => bool;
+        /// DIDs whose balance of a token can't be moved
+        pub FrozenAccounts get(fn is_frozen): double_map hasher(blake2_128_concat) Did, hasher(twox_64_concat) CurrencyCode => bool;
+        /// Most a token can mint per period, set by its owner or root
+        pub MintRateLimits get(fn mint_rate_limit): map hasher(blake2_128_concat) CurrencyCode => Option<MintRateLimit>;
+        /// Amount of a token minted in its latest rate limit window
+        pub MintWindows get(fn mint_window): map hasher(blake2_128_concat) CurrencyCode => MintWindow;
+        /// Who can send and receive a token, set by its owner or root
+        pub TransferRestrictions get(fn transfer_restriction): map hasher(blake2_128_concat) CurrencyCode => TransferRestriction;
+        /// DIDs allowed to send and receive a token restricted to a whitelist
+        pub TransferWhitelist get(fn is_whitelisted): double_map hasher(blake2_128_concat) CurrencyCode, hasher(blake2_128_concat) Did => bool;
+        /// Number of redemption requests that have been made
+        RedemptionCount get(fn redemption_count): RedemptionIndex;
+        /// Redemption requests waiting for the token owner
+        pub Redemptions get(fn redemption): map hasher(twox_64_concat) RedemptionIndex => Option<RedemptionRequest>;
+        /// Amount a spender DID may transfer out of an owner DID's balance of a token
+        pub Allowances get(fn allowance): double_map hasher(blake2_128_concat) (Did, Did), hasher(twox_64_concat) CurrencyCode => TokenBalance;
         /// To get the owner of the token
         pub TokenIssuer get(fn token_issuer): map hasher(blake2_128_concat) CurrencyCode => Did;
         // Counter for currency
@@ -148,11 +205,18 @@ decl_storage! {
     }
     add_extra_genesis {
         config(endowed_accounts): Vec<(did::Did, CurrencyCode, TokenBalance)>;
+        config(issued_tokens): Vec<(vc::VCid, TokenBalance)>;
 
         build(|config: &GenesisConfig| {
             config.endowed_accounts.iter().for_each(|(account_id, currenct_code, initial_balance)| {
-                <Accounts<T>>::mutate(account_id, currenct_code, |account_data| account_data.data.free = *initial_balance)
-            })
+                <Accounts<T>>::mutate(account_id, currenct_code, |account_data| account_data.data.free = *initial_balance);
+                TotalIssuance::mutate(currenct_code, |issued| {
+                    *issued = issued.checked_add(*initial_balance).expect("Genesis issuance must not overflow")
+                });
+            });
+            <Module<T>>::initialize_tokens(&config.issued_tokens);
+            // a new chain starts with the latest storage layout
+            PalletVersion::put(StorageVersion::V5_0_0);
         })
     }
 }
@@ -173,6 +237,42 @@ decl_event!(
         TransferredWithVC(CurrencyCode, Did, TokenBalance, vc::VCid),
         /// Token Balance Set
         TokenBalanceSet(CurrencyCode, Did, TokenBalance),
+        /// Token metadata updated by the owner [CurrencyCode, owner]
+        TokenMetadataUpdated(CurrencyCode, Did),
+        /// All transfers of the token halted [CurrencyCode]
+        TokenPaused(CurrencyCode),
+        /// Transfers of the token resumed [CurrencyCode]
+        TokenUnpaused(CurrencyCode),
+        /// Balance of the token frozen for a DID [CurrencyCode, did]
+        AccountFrozen(CurrencyCode, Did),
+        /// Balance of the token thawed for a DID [CurrencyCode, did]
+        AccountThawed(CurrencyCode, Did),
+        /// Lock set or extended on a balance [CurrencyCode, did, lock_id, amount]
+        LockSet(CurrencyCode, Did, LockIdentifier, TokenBalance),
+        /// Lock removed from a balance [CurrencyCode, did, lock_id]
+        LockRemoved(CurrencyCode, Did, LockIdentifier),
+        /// Allowance of a spender set [CurrencyCode, owner, spender, allowance]
+        Approval(CurrencyCode, Did, Did, TokenBalance),
+        /// Transfer of a batch succeeded [CurrencyCode, from, to, amount, memo]
+        TransferredWithMemo(CurrencyCode, Did, Did, TokenBalance, Option<Memo>),
+        /// Transfer of a best-effort batch failed and was skipped [CurrencyCode, index, error]
+        BatchTransferFailed(CurrencyCode, u32, DispatchError),
+        /// Mint rate limit of the token set or removed [CurrencyCode, limit]
+        MintRateLimitSet(CurrencyCode, Option<MintRateLimit>),
+        /// Balance burnt by its holder [CurrencyCode, holder, amount]
+        TokenBurned(CurrencyCode, Did, TokenBalance),
+        /// Holder asked the token owner to redeem a reserved amount [index, CurrencyCode, holder, amount, reference]
+        RedemptionRequested(RedemptionIndex, CurrencyCode, Did, TokenBalance, Memo),
+        /// Redemption paid off-chain and the reserved amount burnt [index, CurrencyCode, holder, amount]
+        RedemptionConfirmed(RedemptionIndex, CurrencyCode, Did, TokenBalance),
+        /// Redemption refused and the reserved amount released [index, CurrencyCode, holder, amount]
+        RedemptionRejected(RedemptionIndex, CurrencyCode, Did, TokenBalance),
+        /// Transfer restriction of the token changed [CurrencyCode, restriction]
+        TransferRestrictionSet(CurrencyCode, TransferRestriction),
+        /// DID added to the whitelist of the token [CurrencyCode, did]
+        AddedToWhitelist(CurrencyCode, Did),
+        /// DID removed from the whitelist of the token [CurrencyCode, did]
+        RemovedFromWhitelist(CurrencyCode, Did),
     }
 );
 
@@ -180,6 +280,10 @@ decl_module! {
     pub struct Module<T: Config> for enum Call where origin: T::Origin {
         type Error = Error<T>;
 
+        const MaxBatchTransfers: u32 = T::MaxBatchTransfers::get();
+
+        const MaxTokenMetadataLength: u32 = T::MaxTokenMetadataLength::get();
+
         fn deposit_event() = default;
 
         /// Transfer some balance to another account.
@@ -267,40 +371,7 @@ decl_module! {
                 }
             };
 
-            let currency_id = Self::get_currency_id();
-            let token_vc: vc::TokenVC =
-                vc::Module::<T>::get_vc(&vc_struct.vc_property)?;
-            let reservable_balance: BalanceOf<T> = token_vc.reservable_balance.try_into().ok().unwrap_or_default();
-
-            // Checking for duplicate currency_code
-            ensure!(!TokenInfo::<T>::contains_key(token_vc.currency_code), Error::<T>::CurrencyCodeAlreadyRegistered);
-
-            // reserve the mui balance required to issue new token
-            T::Currency::reserve(&owner, reservable_balance)?;
-
-            // set total issuance to amount
-            TotalIssuance::mutate(token_vc.currency_code, |issued| {
-                *issued = issued.checked_add(amount).unwrap_or_else(|| {
-                    *issued
-                })
-            });
-
-            // allocate total issuance to the destination account - the token central bank
-            Self::set_free_balance(token_vc.currency_code, &owner, amount);
-
-            // set decimal, nonce, currency code and token_name of the destination account
-            Self::set_fields(vc_struct.owner, currency_id, token_vc.clone(), token_vc.token_name.to_vec());
-
-            let dest_did = did::Module::<T>::get_did_from_account_id(&owner);
-            // store the token issuer/owner for lookup
-            TokenIssuer::insert(token_vc.currency_code, dest_did);
-
-            // update vc's is_used flag as used
-            vc::Module::<T>::set_is_used_flag(vc_id);
-
-            Self::set_currency_id(currency_id);
-
-            Self::deposit_event(Event::TokenIssued(token_vc.currency_code, dest_did, amount, vc_id));
+            Self::do_issue_token(&owner, vc_id, &vc_struct, amount)?;
         }
 
         /// Slash the balance from the issuer account
@@ -315,7 +386,7 @@ decl_module! {
             let sender = ensure_signed(origin)?;
             let vc_struct = Self::validate_vc(&sender, &vc_id, &vc::VCType::SlashTokens, Error::<T>::InvalidVC)?;
             let slash_vc: vc::SlashMintTokens =
-                vc::Module::<T>::get_vc::<vc::SlashMintTokens>(&vc_struct.vc_property)?;
+                T::VcVerifier::decode_vc_property(&vc_struct)?;
             let amount: TokenBalance = slash_vc.amount.try_into().ok().unwrap_or_default();
 
             let currency_id = Self::get_ccy_id_from_ccy_code(&slash_vc.currency_code);
@@ -327,7 +398,7 @@ decl_module! {
             <Self as MultiCurrency<T::AccountId>>::slash(currency_id, &vc_owner, amount);
 
             // update vc's is_used flag as used
-            vc::Module::<T>::set_is_used_flag(vc_id);
+            T::VcVerifier::consume_vc(&vc_id);
 
             Self::deposit_event(Event::TokenSlashed(slash_vc.currency_code, issuer, amount, vc_id));
         }
@@ -347,17 +418,21 @@ decl_module! {
             let vc_struct =
                 Self::validate_vc(&sender, &vc_id, &vc::VCType::MintTokens, Error::<T>::InvalidVC)?;
             let mint_vc: vc::SlashMintTokens =
-                vc::Module::<T>::get_vc::<vc::SlashMintTokens>(&vc_struct.vc_property)?;
+                T::VcVerifier::decode_vc_property(&vc_struct)?;
             let amount: TokenBalance = mint_vc.amount.try_into().ok().unwrap_or_default();
 
             let currency_id = Self::get_ccy_id_from_ccy_code(&mint_vc.currency_code);
             let issuer =  TokenIssuer::get(mint_vc.currency_code);
 
             let vc_owner = Self::get_vc_owner::<vc::SlashMintTokens>(&vc_struct)?;
+            let window = Self::ensure_can_mint(mint_vc.currency_code, amount)?;
             <Self as MultiCurrency<T::AccountId>>::deposit(currency_id, &vc_owner, amount)?;
+            if let Some(window) = window {
+                MintWindows::insert(mint_vc.currency_code, window);
+            }
 
             // update vc's is_used flag as used
-            vc::Module::<T>::set_is_used_flag(vc_id);
+            T::VcVerifier::consume_vc(&vc_id);
 
             Self::deposit_event(Event::TokenMinted(mint_vc.currency_code, issuer, amount, vc_id));
         }
@@ -401,7 +476,7 @@ decl_module! {
             let vc_struct =
                 Self::validate_vc(&sender, &vc_id, &vc::VCType::TokenTransferVC, Error::<T>::InvalidVC)?;
             let transfer_vc: vc::TokenTransferVC =
-                vc::Module::<T>::get_vc::<vc::TokenTransferVC>(&vc_struct.vc_property)?;
+                T::VcVerifier::decode_vc_property(&vc_struct)?;
             let currency_id = Self::get_ccy_id_from_ccy_code(&transfer_vc.currency_code);
             let amount: TokenBalance = transfer_vc.amount.try_into().ok().unwrap_or_default();
             let vc_owner = Self::get_vc_owner::<vc::TokenTransferVC>(&vc_struct)?;
@@ -409,7 +484,7 @@ decl_module! {
             <Self as MultiCurrency<T::AccountId>>::transfer(currency_id, &vc_owner, &to, amount)?;
 
             // update vc's is_used flag as used
-            vc::Module::<T>::set_is_used_flag(vc_id);
+            T::VcVerifier::consume_vc(&vc_id);
 
             let dest_did = did::Module::<T>::get_did_from_account_id(&to);
 
@@ -442,6 +517,300 @@ decl_module! {
             Self::deposit_event(Event::TokenBalanceSet(currency_code, dest, amount));
         }
 
+        /// Set the display metadata of a token
+        ///
+        /// The dispatch origin for this call must be `Signed` by the token owner.
+        /// Every field is bounded by `MaxTokenMetadataLength`.
+        #[weight = 1]
+        pub fn set_metadata(
+            origin,
+            currency_code: CurrencyCode,
+            display_name: Vec<u8>,
+            logo_uri: Vec<u8>,
+            website: Vec<u8>,
+            description: Vec<u8>,
+        ) {
+            let sender = ensure_signed(origin)?;
+            ensure!(TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+            let token_owner = Self::ensure_token_owner(&sender, currency_code)?;
+
+            let max_length = T::MaxTokenMetadataLength::get() as usize;
+            ensure!(
+                [&display_name, &logo_uri, &website, &description].iter().all(|field| field.len() <= max_length),
+                Error::<T>::MetadataTooLong
+            );
+
+            TokenMetadataOf::insert(currency_code, TokenMetadata {
+                display_name,
+                logo_uri,
+                website,
+                description,
+            });
+
+            Self::deposit_event(Event::TokenMetadataUpdated(currency_code, token_owner));
+        }
+
+        /// Limit the amount of a token minted in each window of `period` blocks, or remove the
+        /// limit with `None`
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn set_mint_rate_limit(origin, currency_code: CurrencyCode, limit: Option<MintRateLimit>) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+
+            match limit {
+                Some(limit) => {
+                    ensure!(limit.period > 0, Error::<T>::ZeroMintPeriod);
+                    MintRateLimits::insert(currency_code, limit);
+                }
+                None => MintRateLimits::remove(currency_code),
+            }
+            MintWindows::remove(currency_code);
+
+            Self::deposit_event(Event::MintRateLimitSet(currency_code, limit));
+        }
+
+        /// Destroy `amount` of the caller's balance, reducing the total issuance
+        #[weight = 1]
+        pub fn burn(origin, currency_code: CurrencyCode, amount: TokenBalance) {
+            let sender = ensure_signed(origin)?;
+            ensure!(TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+            let currency_id = Self::get_ccy_id_from_ccy_code(&currency_code);
+
+            <Self as MultiCurrency<T::AccountId>>::withdraw(currency_id, &sender, amount)?;
+
+            let holder = did::Module::<T>::get_did_from_account_id(&sender);
+            Self::deposit_event(Event::TokenBurned(currency_code, holder, amount));
+        }
+
+        /// Reserve `amount` of the caller's balance and ask the token owner to redeem it
+        ///
+        /// `reference` identifies the redemption to the owner off-chain, e.g. a payment reference.
+        #[weight = 1]
+        pub fn request_redemption(origin, currency_code: CurrencyCode, amount: TokenBalance, reference: Memo) {
+            let sender = ensure_signed(origin)?;
+            ensure!(TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+            ensure!(reference.is_valid(), Error::<T>::InvalidMemoLength);
+            let currency_id = Self::get_ccy_id_from_ccy_code(&currency_code);
+            let index = Self::redemption_count();
+            let next_index = index.checked_add(1).ok_or(Error::<T>::RedemptionCountOverflow)?;
+
+            <Self as MultiReservableCurrency<T::AccountId>>::reserve(currency_id, &sender, amount)?;
+
+            let holder = did::Module::<T>::get_did_from_account_id(&sender);
+            RedemptionCount::put(next_index);
+            Redemptions::insert(index, RedemptionRequest {
+                holder,
+                currency_code,
+                amount,
+                reference: reference.clone(),
+            });
+
+            Self::deposit_event(Event::RedemptionRequested(index, currency_code, holder, amount, reference));
+        }
+
+        /// Confirm the redemption was paid off-chain, burning the reserved amount
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn confirm_redemption(origin, index: RedemptionIndex) {
+            let request = Self::redemption(index).ok_or(Error::<T>::RedemptionNotFound)?;
+            Self::ensure_root_or_token_owner(origin, request.currency_code)?;
+            let holder = did::Module::<T>::get_accountid_from_did(&request.holder)?;
+            let currency_id = Self::get_ccy_id_from_ccy_code(&request.currency_code);
+
+            let remaining = <Self as MultiReservableCurrency<T::AccountId>>::slash_reserved(currency_id, &holder, request.amount);
+            Redemptions::remove(index);
+
+            Self::deposit_event(Event::RedemptionConfirmed(index, request.currency_code, request.holder, request.amount - remaining));
+        }
+
+        /// Refuse the redemption, releasing the reserved amount to the holder
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn reject_redemption(origin, index: RedemptionIndex) {
+            let request = Self::redemption(index).ok_or(Error::<T>::RedemptionNotFound)?;
+            Self::ensure_root_or_token_owner(origin, request.currency_code)?;
+            let holder = did::Module::<T>::get_accountid_from_did(&request.holder)?;
+            let currency_id = Self::get_ccy_id_from_ccy_code(&request.currency_code);
+
+            let remaining = <Self as MultiReservableCurrency<T::AccountId>>::unreserve(currency_id, &holder, request.amount);
+            Redemptions::remove(index);
+
+            Self::deposit_event(Event::RedemptionRejected(index, request.currency_code, request.holder, request.amount - remaining));
+        }
+
+        /// Choose who can send and receive a token
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn set_transfer_restriction(origin, currency_code: CurrencyCode, restriction: TransferRestriction) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+            TransferRestrictions::insert(currency_code, restriction.clone());
+            Self::deposit_event(Event::TransferRestrictionSet(currency_code, restriction));
+        }
+
+        /// Allow a DID to send and receive a token restricted to a whitelist
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn add_to_whitelist(origin, currency_code: CurrencyCode, did: Did) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+            TransferWhitelist::insert(currency_code, did, true);
+            Self::deposit_event(Event::AddedToWhitelist(currency_code, did));
+        }
+
+        /// Remove a DID from the whitelist of a token
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn remove_from_whitelist(origin, currency_code: CurrencyCode, did: Did) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+            TransferWhitelist::remove(currency_code, did);
+            Self::deposit_event(Event::RemovedFromWhitelist(currency_code, did));
+        }
+
+        /// Halt all transfers and withdrawals of a token
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn pause(origin, currency_code: CurrencyCode) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+            PausedTokens::insert(currency_code, true);
+            Self::deposit_event(Event::TokenPaused(currency_code));
+        }
+
+        /// Resume transfers and withdrawals of a paused token
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn unpause(origin, currency_code: CurrencyCode) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+            PausedTokens::remove(currency_code);
+            Self::deposit_event(Event::TokenUnpaused(currency_code));
+        }
+
+        /// Prevent the given DID from moving its balance of a token
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn freeze(origin, currency_code: CurrencyCode, did: Did) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+            FrozenAccounts::insert(did, currency_code, true);
+            Self::deposit_event(Event::AccountFrozen(currency_code, did));
+        }
+
+        /// Allow a frozen DID to move its balance of a token again
+        ///
+        /// The dispatch origin for this call must be `Root` or `Signed` by the token owner.
+        #[weight = 1]
+        pub fn thaw(origin, currency_code: CurrencyCode, did: Did) {
+            Self::ensure_root_or_token_owner(origin, currency_code)?;
+            FrozenAccounts::remove(did, currency_code);
+            Self::deposit_event(Event::AccountThawed(currency_code, did));
+        }
+
+        /// Allow `spender` to transfer up to `amount` of the caller's balance
+        ///
+        /// Replaces any existing allowance of the spender for the token.
+        #[weight = 1]
+        pub fn approve(origin, spender: Did, currency_code: CurrencyCode, amount: TokenBalance) {
+            let owner = Self::ensure_can_approve(origin, &spender, currency_code)?;
+            Self::set_allowance(currency_code, owner, spender, amount);
+        }
+
+        /// Increase the allowance of `spender` by `amount`
+        #[weight = 1]
+        pub fn increase_allowance(origin, spender: Did, currency_code: CurrencyCode, amount: TokenBalance) {
+            let owner = Self::ensure_can_approve(origin, &spender, currency_code)?;
+            let allowance = Self::allowance((owner, spender), currency_code)
+                .checked_add(amount)
+                .ok_or(Error::<T>::BalanceOverflow)?;
+            Self::set_allowance(currency_code, owner, spender, allowance);
+        }
+
+        /// Decrease the allowance of `spender` by `amount`
+        #[weight = 1]
+        pub fn decrease_allowance(origin, spender: Did, currency_code: CurrencyCode, amount: TokenBalance) {
+            let owner = Self::ensure_can_approve(origin, &spender, currency_code)?;
+            let allowance = Self::allowance((owner, spender), currency_code)
+                .checked_sub(amount)
+                .ok_or(Error::<T>::InsufficientAllowance)?;
+            Self::set_allowance(currency_code, owner, spender, allowance);
+        }
+
+        /// Transfer `amount` from `owner` to `dest` out of the allowance given to the caller
+        #[weight = 1]
+        pub fn transfer_from(
+            origin,
+            owner: Did,
+            dest: <T::Lookup as StaticLookup>::Source,
+            currency_code: CurrencyCode,
+            amount: TokenBalance,
+        ) {
+            let sender = ensure_signed(origin)?;
+            let to = T::Lookup::lookup(dest)?;
+            ensure!(did::Module::<T>::does_did_exist(&to), Error::<T>::RecipentDIDNotRegistered);
+            let spender = did::Module::<T>::get_did_from_account_id(&sender);
+            let from = did::Module::<T>::get_accountid_from_did(&owner)?;
+
+            let allowance = Self::allowance((owner, spender), currency_code)
+                .checked_sub(amount)
+                .ok_or(Error::<T>::InsufficientAllowance)?;
+            let currency_id = Self::get_ccy_id_from_ccy_code(&currency_code);
+            <Self as MultiCurrency<_>>::transfer(currency_id, &from, &to, amount)?;
+            Self::set_allowance(currency_code, owner, spender, allowance);
+
+            let dest_did = did::Module::<T>::get_did_from_account_id(&to);
+            Self::deposit_event(Event::Transferred(currency_code, owner, dest_did, amount));
+        }
+
+        /// Transfer a token to many accounts, each transfer with an optional memo
+        ///
+        /// The dispatch origin for this call must be `Signed` by the transactor.
+        ///
+        /// The whole batch is reverted if any transfer fails, unless `best_effort` is set in
+        /// which case failed transfers are skipped and reported with `BatchTransferFailed`.
+        ///
+        /// # <weight>
+        /// - Complexity: `O(N)` where `N` is the number of transfers
+        /// - Weight of `transfer` for each transfer
+        /// # </weight>
+        #[weight = T::WeightInfo::transfer().saturating_mul(transfers.len().max(1) as Weight)]
+        #[transactional]
+        pub fn batch_transfer(
+            origin,
+            currency_code: CurrencyCode,
+            transfers: Vec<(<T::Lookup as StaticLookup>::Source, TokenBalance, Option<Memo>)>,
+            best_effort: bool,
+        ) {
+            let from = ensure_signed(origin)?;
+            ensure!(TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+            ensure!(
+                transfers.len() <= T::MaxBatchTransfers::get() as usize,
+                Error::<T>::TooManyTransfers
+            );
+            let currency_id = Self::get_ccy_id_from_ccy_code(&currency_code);
+            let source_did = did::Module::<T>::get_did_from_account_id(&from);
+
+            for (index, (dest, amount, memo)) in transfers.into_iter().enumerate() {
+                let result = frame_support::storage::with_transaction(|| {
+                    match Self::transfer_with_memo(currency_code, currency_id, &from, source_did, dest, amount, memo) {
+                        Ok(()) => sp_runtime::TransactionOutcome::Commit(Ok(())),
+                        Err(e) => sp_runtime::TransactionOutcome::Rollback(Err(e)),
+                    }
+                });
+                match result {
+                    Ok(()) => {}
+                    Err(e) if best_effort => {
+                        Self::deposit_event(Event::BatchTransferFailed(currency_code, index as u32, e));
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
         fn on_runtime_upgrade() -> frame_support::weights::Weight {
             migration::migrate::<T>()
         }
@@ -470,11 +839,107 @@ decl_error! {
         /// Token Amount Overflow
         TokenAmountOverflow,
         /// Only Token owner can set other's balance
-        NotAllowed
+        NotAllowed,
+        /// No token is registered with the currency code
+        TokenNotFound,
+        /// A token metadata field exceeds the maximum length
+        MetadataTooLong,
+        /// Transfers of the token are paused
+        TokenIsPaused,
+        /// The account's balance of the token is frozen
+        AccountIsFrozen,
+        /// The spender did must be valid
+        SpenderDIDNotRegistered,
+        /// The transfer or decrease exceeds the allowance given to the spender
+        InsufficientAllowance,
+        /// The batch holds more than `MaxBatchTransfers` transfers
+        TooManyTransfers,
+        /// Memo length too long
+        InvalidMemoLength,
+        /// Issuing or minting the amount would exceed the supply cap of the token
+        SupplyCapExceeded,
+        /// Minting the amount would exceed the rate limit of the current period
+        MintRateLimitExceeded,
+        /// Period of a mint rate limit is zero
+        ZeroMintPeriod,
+        /// No redemption request exists with the index
+        RedemptionNotFound,
+        /// No more redemption requests can be indexed
+        RedemptionCountOverflow,
+        /// The sender or the recipient is not on the whitelist of the token
+        NotWhitelisted,
+        /// The sender or the recipient has no active KYC VC from the issuer designated for the token
+        MissingKycCredential,
     }
 }
 
 impl<T: Config> Module<T> {
+    /// Issue a new token backed by the given TokenVC and credit the entire supply to `owner`
+    fn do_issue_token(
+        owner: &T::AccountId,
+        vc_id: vc::VCid,
+        vc_struct: &vc::VC<T::Hash>,
+        amount: TokenBalance,
+    ) -> DispatchResult {
+        let currency_id = Self::get_currency_id();
+        let token_vc: vc::TokenVC = T::VcVerifier::decode_vc_property(vc_struct)?;
+        let reservable_balance: BalanceOf<T> = token_vc.reservable_balance.try_into().ok().unwrap_or_default();
+
+        // Checking for duplicate currency_code
+        ensure!(!TokenInfo::<T>::contains_key(token_vc.currency_code), Error::<T>::CurrencyCodeAlreadyRegistered);
+
+        // genesis endowments may already hold some of the currency
+        let new_total = Self::total_issuance(token_vc.currency_code)
+            .checked_add(amount)
+            .ok_or(Error::<T>::TotalIssuanceOverflow)?;
+        if let Some(max_supply) = token_vc.max_supply {
+            ensure!(new_total <= max_supply, Error::<T>::SupplyCapExceeded);
+        }
+        let new_balance = Self::accounts(did::Module::<T>::get_did_from_account_id(owner), token_vc.currency_code)
+            .data
+            .free
+            .checked_add(amount)
+            .ok_or(Error::<T>::TokenAmountOverflow)?;
+
+        // reserve the mui balance required to issue new token
+        T::Currency::reserve(owner, reservable_balance)?;
+
+        TotalIssuance::insert(token_vc.currency_code, new_total);
+
+        // allocate the issued amount to the destination account - the token central bank
+        Self::set_free_balance(token_vc.currency_code, owner, new_balance);
+
+        // set decimal, nonce, currency code and token_name of the destination account
+        Self::set_fields(vc_struct.owner, currency_id, token_vc.clone(), token_vc.token_name.to_vec());
+
+        let dest_did = did::Module::<T>::get_did_from_account_id(owner);
+        // store the token issuer/owner for lookup
+        TokenIssuer::insert(token_vc.currency_code, dest_did);
+
+        // update vc's is_used flag as used
+        T::VcVerifier::consume_vc(&vc_id);
+
+        Self::set_currency_id(currency_id);
+
+        Self::deposit_event(Event::TokenIssued(token_vc.currency_code, dest_did, amount, vc_id));
+        Ok(())
+    }
+
+    /// Issue the tokens backed by genesis TokenVCs
+    ///
+    /// Panics if a TokenVC is missing, inactive, already used or its owner can't reserve the
+    /// required balance
+    fn initialize_tokens(issued_tokens: &[(vc::VCid, TokenBalance)]) {
+        for (vc_id, amount) in issued_tokens.iter() {
+            let vc_struct = Self::get_vc_struct(vc_id, &vc::VCType::TokenVC, Error::<T>::InvalidVC)
+                .expect("Genesis token must be backed by an active TokenVC");
+            let owner = did::Module::<T>::get_accountid_from_did(&vc_struct.owner)
+                .expect("Owner of genesis TokenVC must be a registered DID");
+            Self::do_issue_token(&owner, *vc_id, &vc_struct, *amount)
+                .expect("Genesis token must be issuable");
+        }
+    }
+
     /// Set free balance of `who` to a new value.
     ///
     /// Note this will not maintain total issuance.
@@ -520,6 +985,7 @@ impl<T: Config> Module<T> {
                 currency_code,
                 decimal: token_vc.decimal,
                 block_number: current_block_no,
+                max_supply: token_vc.max_supply,
             },
         );
         Accounts::<T>::mutate(identifier, token_vc.currency_code, |account_data| {
@@ -551,20 +1017,12 @@ impl<T: Config> Module<T> {
         TokenCurrencyCounter::<T>::put(currency_id);
     }
 
-    /// Get VC Owner
+    /// Get the account of the owner of the VC linked to the given VC
     fn get_vc_owner<G: codec::Decode + vc::HasVCId>(
         vc_struct: &vc::VC<T::Hash>,
     ) -> Result<T::AccountId, DispatchError> {
-        let vc_property: G = vc::Module::<T>::get_vc::<G>(&vc_struct.vc_property)?;
-
-        let (token_vc_struct, _) =
-            if let Some((vc_struct, vc_status)) = vc::VCs::<T>::get(&vc_property.vc_id()) {
-                (vc_struct, vc_status)
-            } else {
-                fail!(vc::Error::<T>::LinkedVCNotFound);
-            };
-
-        let owners_acc_id = did::Module::<T>::get_accountid_from_did(&token_vc_struct.owner)?;
+        let owner = T::VcVerifier::get_linked_vc_owner::<G>(vc_struct)?;
+        let owners_acc_id = did::Module::<T>::get_accountid_from_did(&owner)?;
 
         Ok(owners_acc_id)
     }
@@ -575,26 +1033,12 @@ impl<T: Config> Module<T> {
         vc_type: &vc::VCType,
         vc_type_error: Error<T>,
     ) -> Result<vc::VC<T::Hash>, DispatchError> {
-        // ensure vc exists
-        let (vc_struct, vc_status) = if let Some((vc_struct, vc_status)) = vc::VCs::<T>::get(&vc_id)
-        {
-            (vc_struct, vc_status)
-        } else {
-            fail!(vc::Error::<T>::VCIdDoesNotExist);
-        };
-
-        // ensure vc is active
-        ensure!(
-            vc_status.eq(&vc::VCStatus::Active),
-            vc::Error::<T>::VCIsNotActive
-        );
+        // ensure vc exists, is active and unused
+        let vc_struct = T::VcVerifier::get_usable_vc(vc_id)?;
 
         // ensure vc_type
         ensure!(vc_struct.vc_type.eq(vc_type), vc_type_error);
 
-        // ensure VC is unused
-        ensure!(!vc_struct.is_vc_used, vc::Error::<T>::VCAlreadyUsed);
-
         Ok(vc_struct)
     }
 
@@ -607,13 +1051,11 @@ impl<T: Config> Module<T> {
     ) -> Result<vc::VC<T::Hash>, DispatchError> {
         let senders_did = did::Module::<T>::get_did_from_account_id(&senders_acccount_id);
 
-        let vc_struct = Self::get_vc_struct(vc_id, vc_type, vc_type_error)?;
+        // ensure vc is usable and sender has associated vc
+        let vc_struct = T::VcVerifier::get_owned_usable_vc(&senders_did, vc_id)?;
 
-        // ensure sender has associated vc
-        ensure!(
-            senders_did.eq(&vc_struct.owner),
-            vc::Error::<T>::DidNotRegisteredWithVC
-        );
+        // ensure vc_type
+        ensure!(vc_struct.vc_type.eq(vc_type), vc_type_error);
 
         Ok(vc_struct)
     }
@@ -634,32 +1076,97 @@ impl<T: Config> Module<T> {
         });
     }
 
-    // Update the account entry for `who` under `currency_id`, given the locks.
-    // fn update_locks(currency_id: T::CurrencyId, who: &T::AccountId, locks: &[BalanceLock<T::Balance>]) {
-    // 	// update account data
-    // 	<Accounts<T>>::mutate(who, currency_id, |account_data| {
-    // 		account_data.frozen = Zero::zero();
-    // 		for lock in locks.iter() {
-    // 			account_data.frozen = account_data.frozen.max(lock.amount);
-    // 		}
-    // 	});
-
-    // 	// update locks
-    // 	let existed = <Locks<T>>::contains_key(who, currency_id);
-    // 	if locks.is_empty() {
-    // 		<Locks<T>>::remove(who, currency_id);
-    // 		if existed {
-    // 			// decrease account ref count when destruct lock
-    // 			frame_system::Module::<T>::dec_ref(who);
-    // 		}
-    // 	} else {
-    // 		<Locks<T>>::insert(who, currency_id, locks);
-    // 		if !existed {
-    // 			// increase account ref count when initialize lock
-    // 			frame_system::Module::<T>::inc_ref(who);
-    // 		}
-    // 	}
-    // }
+    /// Update the account entry for `did` under `currency_code`, given the locks.
+    ///
+    /// The frozen balance is the largest lock, locks overlap rather than add up.
+    fn update_locks(currency_code: CurrencyCode, did: Did, locks: &[BalanceLock<TokenBalance>]) {
+        if locks.len() as u32 > T::MaxLocks::get() {
+            frame_support::debug::warn!(
+                "Warning: A user has more token locks than expected. \
+                A runtime configuration adjustment may be needed."
+            );
+        }
+        <Accounts<T>>::mutate(did, currency_code, |account_data| {
+            account_data.data.frozen = locks
+                .iter()
+                .map(|lock| lock.amount)
+                .max()
+                .unwrap_or_else(Zero::zero);
+        });
+
+        if locks.is_empty() {
+            Locks::remove(did, currency_code);
+        } else {
+            Locks::insert(did, currency_code, locks);
+        }
+    }
+
+    /// Get the details and display metadata of the token, used by the `tokens_metadata` RPC
+    pub fn get_token_metadata(currency_code: CurrencyCode) -> Option<TokenMetadataDetails> {
+        let details = TokenData::get(currency_code)?;
+        Some(TokenMetadataDetails {
+            details,
+            issuer: TokenIssuer::get(currency_code),
+            metadata: TokenMetadataOf::get(currency_code).unwrap_or_default(),
+        })
+    }
+
+    /// Get the amount of the token that can still be minted, used by the `tokens_mintableSupply` RPC
+    ///
+    /// Returns `None` if no token is registered with the currency code
+    pub fn mintable_supply(currency_code: CurrencyCode) -> Option<MintableSupply> {
+        let details = TokenData::get(currency_code)?;
+        let remaining_supply = details
+            .max_supply
+            .map(|max_supply| max_supply.saturating_sub(Self::total_issuance(currency_code)));
+        let remaining_in_period = Self::mint_rate_limit(currency_code).map(|limit| {
+            let minted = Self::current_mint_window(currency_code, &limit).minted;
+            limit.max_amount.saturating_sub(minted)
+        });
+        Some(MintableSupply {
+            remaining_supply,
+            remaining_in_period,
+        })
+    }
+
+    /// Get the rate limit window the current block belongs to, with the amount minted in it
+    fn current_mint_window(currency_code: CurrencyCode, limit: &MintRateLimit) -> MintWindow {
+        let now: BlockNumber = <frame_system::Module<T>>::block_number()
+            .try_into()
+            .ok()
+            .unwrap_or_default();
+        let start = now - now % limit.period;
+        let window = Self::mint_window(currency_code);
+        if window.start == start {
+            window
+        } else {
+            MintWindow { start, minted: 0 }
+        }
+    }
+
+    /// Ensure minting `amount` respects the supply cap and the rate limit of the token
+    ///
+    /// Returns the rate limit window updated with the amount, to store once minted
+    fn ensure_can_mint(currency_code: CurrencyCode, amount: TokenBalance) -> Result<Option<MintWindow>, DispatchError> {
+        if let Some(max_supply) = Self::token_data(currency_code).and_then(|details| details.max_supply) {
+            let new_total = Self::total_issuance(currency_code)
+                .checked_add(amount)
+                .ok_or(Error::<T>::TotalIssuanceOverflow)?;
+            ensure!(new_total <= max_supply, Error::<T>::SupplyCapExceeded);
+        }
+        match Self::mint_rate_limit(currency_code) {
+            Some(limit) => {
+                let mut window = Self::current_mint_window(currency_code, &limit);
+                window.minted = window
+                    .minted
+                    .checked_add(amount)
+                    .filter(|minted| *minted <= limit.max_amount)
+                    .ok_or(Error::<T>::MintRateLimitExceeded)?;
+                Ok(Some(window))
+            }
+            None => Ok(None),
+        }
+    }
 
     /// Ensure the given sender is owner of the given currency
     fn ensure_token_owner(sender: &T::AccountId, currency_code: CurrencyCode) -> Result<Did, DispatchError> {
@@ -669,6 +1176,58 @@ impl<T: Config> Module<T> {
         Ok(token_owner)
     }
 
+    /// Ensure the origin is root or signed by the owner of the given currency
+    fn ensure_root_or_token_owner(origin: T::Origin, currency_code: CurrencyCode) -> DispatchResult {
+        ensure!(TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+        if ensure_root(origin.clone()).is_err() {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_token_owner(&sender, currency_code)?;
+        }
+        Ok(())
+    }
+
+    /// Transfer `amount` to `dest` as part of a batch and emit the transfer with its memo
+    fn transfer_with_memo(
+        currency_code: CurrencyCode,
+        currency_id: T::CurrencyId,
+        from: &T::AccountId,
+        source_did: Did,
+        dest: <T::Lookup as StaticLookup>::Source,
+        amount: TokenBalance,
+        memo: Option<Memo>,
+    ) -> DispatchResult {
+        if let Some(memo) = &memo {
+            ensure!(memo.is_valid(), Error::<T>::InvalidMemoLength);
+        }
+        let to = T::Lookup::lookup(dest)?;
+        ensure!(did::Module::<T>::does_did_exist(&to), Error::<T>::RecipentDIDNotRegistered);
+        <Self as MultiCurrency<_>>::transfer(currency_id, from, &to, amount)?;
+
+        let dest_did = did::Module::<T>::get_did_from_account_id(&to);
+        Self::deposit_event(Event::TransferredWithMemo(currency_code, source_did, dest_did, amount, memo));
+        Ok(())
+    }
+
+    /// Ensure the signed caller can give an allowance of the token to `spender`
+    ///
+    /// Returns the DID of the caller
+    fn ensure_can_approve(origin: T::Origin, spender: &Did, currency_code: CurrencyCode) -> Result<Did, DispatchError> {
+        let sender = ensure_signed(origin)?;
+        ensure!(TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+        ensure!(did::Module::<T>::did_registered(spender), Error::<T>::SpenderDIDNotRegistered);
+        Ok(did::Module::<T>::get_did_from_account_id(&sender))
+    }
+
+    /// Set the allowance of `spender` over the balance of `owner`
+    fn set_allowance(currency_code: CurrencyCode, owner: Did, spender: Did, amount: TokenBalance) {
+        if amount.is_zero() {
+            Allowances::remove((owner, spender), currency_code);
+        } else {
+            Allowances::insert((owner, spender), currency_code, amount);
+        }
+        Self::deposit_event(Event::Approval(currency_code, owner, spender, amount));
+    }
+
     /// Gets updated token balance of owner
     /// Validate Whether balance can be set
     /// Also checks if overflow or underflow occurs
@@ -687,6 +1246,35 @@ impl<T: Config> Module<T> {
         }
     }
 
+    /// Sum of the balances, free and reserved, held in `Accounts` for each currency code
+    fn account_totals() -> BTreeMap<CurrencyCode, TokenBalance> {
+        let mut totals = BTreeMap::new();
+        for (_, currency_code, account) in Accounts::<T>::iter() {
+            let total: &mut TokenBalance = totals.entry(currency_code).or_default();
+            *total = total.saturating_add(account.data.total());
+        }
+        totals
+    }
+
+    /// Check that the total issuance of every currency equals the sum of its account balances
+    ///
+    /// Iterates over all the accounts, only used by the tests.
+    #[cfg(test)]
+    fn check_total_issuance() -> Result<(), &'static str> {
+        let totals = Self::account_totals();
+        for (currency_code, issuance) in TotalIssuance::iter() {
+            if totals.get(&currency_code).copied().unwrap_or_default() != issuance {
+                return Err("TotalIssuance does not match the sum of account balances");
+            }
+        }
+        for (currency_code, total) in totals {
+            if TotalIssuance::get(currency_code) != total {
+                return Err("Account balances held for a currency without matching TotalIssuance");
+            }
+        }
+        Ok(())
+    }
+
     /// Set token balance to given did
     /// Balance will be transfered from/to owner's did to keep total issuance same
     fn set_token_balance(currency_code: CurrencyCode, token_owner: Did, dest: Did, amount: TokenBalance) -> DispatchResult {
@@ -725,7 +1313,8 @@ impl<T: Config> MultiCurrency<T::AccountId> for Module<T> {
     }
 
     // Ensure that an account can withdraw from their free balance given any
-    // existing withdrawal restrictions like locks and vesting balance.
+    // existing withdrawal restrictions like locks, vesting balance, a paused
+    // token or a frozen account.
     // Is a no-op if amount to be withdrawn is zero.
     fn ensure_can_withdraw(
         currency_id: Self::CurrencyId,
@@ -736,10 +1325,12 @@ impl<T: Config> MultiCurrency<T::AccountId> for Module<T> {
             return Ok(());
         }
         let did = did::Module::<T>::get_did_from_account_id(who);
+        let currency_code = Self::get_ccy_code_from_id_code(&currency_id);
+        ensure!(!Self::is_paused(currency_code), Error::<T>::TokenIsPaused);
+        ensure!(!Self::is_frozen(did, currency_code), Error::<T>::AccountIsFrozen);
         let new_balance = Self::free_balance(currency_id, who)
             .checked_sub(amount)
             .ok_or(Error::<T>::BalanceTooLow)?;
-        let currency_code = Self::get_ccy_code_from_id_code(&currency_id);
         ensure!(
             new_balance >= Self::accounts(did, currency_code).data.frozen(),
             Error::<T>::LiquidityRestrictions
@@ -761,12 +1352,19 @@ impl<T: Config> MultiCurrency<T::AccountId> for Module<T> {
         }
         Self::ensure_can_withdraw(currency_id, from, amount)?;
 
+        let currency_code = Self::get_ccy_code_from_id_code(&currency_id);
+        T::TransferPolicy::ensure_can_transfer(
+            currency_code,
+            &did::Module::<T>::get_did_from_account_id(from),
+            &did::Module::<T>::get_did_from_account_id(to),
+            amount,
+        )?;
+
         let from_balance = Self::free_balance(currency_id, from);
         let to_balance = Self::free_balance(currency_id, to)
             .checked_add(amount)
             .ok_or(Error::<T>::BalanceOverflow)?;
         // Cannot underflow because ensure_can_withdraw check
-        let currency_code = Self::get_ccy_code_from_id_code(&currency_id);
         Self::set_free_balance(currency_code, from, from_balance - amount);
         Self::set_free_balance(currency_code, to, to_balance);
 
@@ -909,64 +1507,97 @@ impl<T: Config> MultiCurrencyExtended<T::AccountId> for Module<T> {
     }
 }
 
-// impl<T: Config> MultiLockableCurrency<T::AccountId> for Module<T> {
-// 	type Moment = T::BlockNumber;
-
-// Set a lock on the balance of `who` under `currency_id`.
-// Is a no-op if lock amount is zero.
-// fn set_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) {
-// 	if amount.is_zero() {
-// 		return;
-// 	}
-// 	let mut new_lock = Some(BalanceLock { id: lock_id, amount });
-// 	let mut locks = Self::locks(who, currency_id)
-// 		.into_iter()
-// 		.filter_map(|lock| {
-// 			if lock.id == lock_id {
-// 				new_lock.take()
-// 			} else {
-// 				Some(lock)
-// 			}
-// 		})
-// 		.collect::<Vec<_>>();
-// 	if let Some(lock) = new_lock {
-// 		locks.push(lock)
-// 	}
-// 	Self::update_locks(currency_id, who, &locks[..]);
-// }
+impl<T: Config> MultiLockableCurrency<T::AccountId> for Module<T> {
+    type Moment = T::BlockNumber;
 
-// Extend a lock on the balance of `who` under `currency_id`.
-// Is a no-op if lock amount is zero
-// fn extend_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) {
-// 	if amount.is_zero() {
-// 		return;
-// 	}
-// 	let mut new_lock = Some(BalanceLock { id: lock_id, amount });
-// 	let mut locks = Self::locks(who, currency_id)
-// 		.into_iter()
-// 		.filter_map(|lock| {
-// 			if lock.id == lock_id {
-// 				new_lock.take().map(|nl| BalanceLock {
-// 					id: lock.id,
-// 					amount: lock.amount.max(nl.amount),
-// 				})
-// 			} else {
-// 				Some(lock)
-// 			}
-// 		})
-// 		.collect::<Vec<_>>();
-// 	if let Some(lock) = new_lock {
-// 		locks.push(lock)
-// 	}
-// 	Self::update_locks(currency_id, who, &locks[..]);
-// }
+    // Set a lock on the balance of `who` under `currency_id`.
+    // Is a no-op if lock amount is zero.
+    fn set_lock(
+        lock_id: LockIdentifier,
+        currency_id: Self::CurrencyId,
+        who: &T::AccountId,
+        amount: Self::Balance,
+    ) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let currency_code = Self::get_ccy_code_from_id_code(&currency_id);
+        let did = did::Module::<T>::get_did_from_account_id(who);
+        let mut new_lock = Some(BalanceLock { id: lock_id, amount });
+        let mut locks = Self::locks(did, currency_code)
+            .into_iter()
+            .filter_map(|lock| {
+                if lock.id == lock_id {
+                    new_lock.take()
+                } else {
+                    Some(lock)
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(lock) = new_lock {
+            locks.push(lock)
+        }
+        Self::update_locks(currency_code, did, &locks[..]);
 
-// fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) {
-// 	let mut locks = Self::locks(who, currency_id);
-// 	locks.retain(|lock| lock.id != lock_id);
-// 	Self::update_locks(currency_id, who, &locks[..]);
-// }
-//}
+        Self::deposit_event(Event::LockSet(currency_code, did, lock_id, amount));
+        Ok(())
+    }
+
+    // Extend a lock on the balance of `who` under `currency_id`.
+    // Is a no-op if lock amount is zero
+    fn extend_lock(
+        lock_id: LockIdentifier,
+        currency_id: Self::CurrencyId,
+        who: &T::AccountId,
+        amount: Self::Balance,
+    ) -> DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let currency_code = Self::get_ccy_code_from_id_code(&currency_id);
+        let did = did::Module::<T>::get_did_from_account_id(who);
+        let mut new_lock = Some(BalanceLock { id: lock_id, amount });
+        let mut locks = Self::locks(did, currency_code)
+            .into_iter()
+            .filter_map(|lock| {
+                if lock.id == lock_id {
+                    new_lock.take().map(|nl| BalanceLock {
+                        id: lock.id,
+                        amount: lock.amount.max(nl.amount),
+                    })
+                } else {
+                    Some(lock)
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(lock) = new_lock {
+            locks.push(lock)
+        }
+        let locked = locks
+            .iter()
+            .find(|lock| lock.id == lock_id)
+            .map_or(amount, |lock| lock.amount);
+        Self::update_locks(currency_code, did, &locks[..]);
+
+        Self::deposit_event(Event::LockSet(currency_code, did, lock_id, locked));
+        Ok(())
+    }
+
+    fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) -> DispatchResult {
+        let currency_code = Self::get_ccy_code_from_id_code(&currency_id);
+        let did = did::Module::<T>::get_did_from_account_id(who);
+        let mut locks = Self::locks(did, currency_code);
+        let count = locks.len();
+        locks.retain(|lock| lock.id != lock_id);
+        if locks.len() == count {
+            return Ok(());
+        }
+        Self::update_locks(currency_code, did, &locks[..]);
+
+        Self::deposit_event(Event::LockRemoved(currency_code, did, lock_id));
+        Ok(())
+    }
+}
 
 impl<T: Config> MultiReservableCurrency<T::AccountId> for Module<T> {
     /// Check if `who` can reserve `value` from their free balance.
@@ -1337,23 +1968,24 @@ where
     }
 }
 
-// impl<T, GetCurrencyId> PalletLockableCurrency<T::AccountId> for CurrencyAdapter<T, GetCurrencyId>
-// where
-// 	T: Config,
-// 	GetCurrencyId: Get<T::CurrencyId>,
-// {
-// 	type Moment = T::BlockNumber;
-// 	type MaxLocks = ();
-
-// 	fn set_lock(id: LockIdentifier, who: &T::AccountId, amount: Self::Balance, _reasons: WithdrawReasons) {
-// 		Module::<T>::set_lock(id, GetCurrencyId::get(), who, amount)
-// 	}
-
-// 	fn extend_lock(id: LockIdentifier, who: &T::AccountId, amount: Self::Balance, _reasons: WithdrawReasons) {
-// 		Module::<T>::extend_lock(id, GetCurrencyId::get(), who, amount)
-// 	}
-
-// 	fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
-// 		Module::<T>::remove_lock(id, GetCurrencyId::get(), who)
-// 	}
-// }
+impl<T, GetCurrencyId> PalletLockableCurrency<T::AccountId> for CurrencyAdapter<T, GetCurrencyId>
+where
+    T: Config,
+    GetCurrencyId: Get<T::CurrencyId>,
+{
+    type Moment = T::BlockNumber;
+    type MaxLocks = T::MaxLocks;
+
+    fn set_lock(id: LockIdentifier, who: &T::AccountId, amount: Self::Balance, _reasons: WithdrawReasons) {
+        // locking never fails
+        let _ = Module::<T>::set_lock(id, GetCurrencyId::get(), who, amount);
+    }
+
+    fn extend_lock(id: LockIdentifier, who: &T::AccountId, amount: Self::Balance, _reasons: WithdrawReasons) {
+        let _ = Module::<T>::extend_lock(id, GetCurrencyId::get(), who, amount);
+    }
+
+    fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+        let _ = Module::<T>::remove_lock(id, GetCurrencyId::get(), who);
+    }
+}
@@ -1,5 +1,5 @@
 use super::*;
-use crate::structs::V1TokenDetails;
+use crate::structs::{V1TokenDetails, V4TokenDetails};
 
 pub mod deprecated {
     use super::*;
@@ -9,7 +9,7 @@ pub mod deprecated {
     decl_storage! {
         trait Store for Module<T: Config> as Tokens {
             pub V1TokenData get(fn v1_token_data) : map hasher(blake2_128_concat) T::CurrencyId => super::V1TokenDetails;
-            pub TokenData get(fn token_data) : map hasher(blake2_128_concat) T::CurrencyId => TokenDetails;
+            pub TokenData get(fn token_data) : map hasher(blake2_128_concat) T::CurrencyId => super::V4TokenDetails;
             pub Locks get(fn locks): double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) T::CurrencyId => Vec<BalanceLock<TokenBalance>>;
             pub TokenIssuer get(fn token_issuer): map hasher(blake2_128_concat) T::CurrencyId => Did;
             pub Accounts get(fn accounts): double_map hasher(blake2_128_concat) did::Did, hasher(twox_64_concat) T::CurrencyId => TokenAccountInfo<T::Index ,TokenAccountData>;
@@ -19,10 +19,42 @@ pub mod deprecated {
     decl_module! {
         pub struct Module<T: Config> for enum Call where origin: T::Origin {}
     }
+
+    /// Token details keyed by currency code, before supply caps
+    pub mod v4 {
+        use super::*;
+
+        decl_storage! {
+            trait Store for Module<T: Config> as Tokens {
+                pub TokenData get(fn token_data) : map hasher(blake2_128_concat) CurrencyCode => V4TokenDetails;
+            }
+        }
+        decl_module! {
+            pub struct Module<T: Config> for enum Call where origin: T::Origin {}
+        }
+    }
 }
 
+/// Run every pending migration step until the storage reaches the latest version
 pub fn migrate<T: Config>() -> frame_support::weights::Weight {
     frame_support::debug::RuntimeLogger::init();
+    let mut weight: Weight = 0;
+    let mut migrated = false;
+    // each step bumps the storage version, a chain a few versions behind catches up in one upgrade
+    while let Some(step_weight) = migrate_step::<T>() {
+        weight = weight.saturating_add(step_weight);
+        migrated = true;
+    }
+    if !migrated {
+        frame_support::debug::info!(" >>> Unused migration!");
+    }
+    weight
+}
+
+/// Migrate the storage from its current version to the next one
+///
+/// Returns `None` once the storage is at the latest version
+fn migrate_step<T: Config>() -> Option<Weight> {
     // Storage migrations should use storage versions for safety.
     match PalletVersion::get() {
         StorageVersion::V1_0_0 => {
@@ -33,7 +65,7 @@ pub fn migrate<T: Config>() -> frame_support::weights::Weight {
             // We transform the storage values from the old into the new format.
             deprecated::TokenData::<T>::translate(
                 |_: T::CurrencyId, token_data: V1TokenDetails| {
-                    Some(TokenDetails {
+                    Some(V4TokenDetails {
                         token_name: token_data.token_name,
                         currency_code: token_data.currency_code,
                         decimal: token_data.decimal,
@@ -45,10 +77,10 @@ pub fn migrate<T: Config>() -> frame_support::weights::Weight {
             // Update storage version.
             PalletVersion::put(StorageVersion::V2_0_0);
             // Very inefficient, mostly here for illustration purposes.
-            let count = TokenData::iter().count();
+            let count = deprecated::TokenData::<T>::iter().count();
 
             // Return the weight consumed by the migration.
-            T::DbWeight::get().reads_writes(count as Weight + 1, count as Weight + 1)
+            Some(T::DbWeight::get().reads_writes(count as Weight + 1, count as Weight + 1))
         }
         StorageVersion::V2_0_0 => {
             let mut new_token_datas = Vec::new();
@@ -59,7 +91,7 @@ pub fn migrate<T: Config>() -> frame_support::weights::Weight {
                 new_token_datas.push((ccy_code, token_details));
             }
             for (ccy_code, token_details) in new_token_datas {
-                TokenData::insert(ccy_code, token_details);
+                deprecated::v4::TokenData::insert(ccy_code, token_details);
             }
 
             let mut new_token_issuers = Vec::new();
@@ -74,10 +106,11 @@ pub fn migrate<T: Config>() -> frame_support::weights::Weight {
             let mut new_locks = Vec::new();
             for (acc_id, ccy_id, bal_dets) in deprecated::Locks::<T>::drain() {
                 let ccy_code = TokenInfoRLookup::<T>::get(ccy_id);
-                new_locks.push((acc_id, ccy_code, bal_dets));
+                let did = did::Module::<T>::get_did_from_account_id(&acc_id);
+                new_locks.push((did, ccy_code, bal_dets));
             }
-            for (acc_id, ccy_code, bal_dets) in new_locks {
-                Locks::<T>::insert(acc_id, ccy_code, bal_dets);
+            for (did, ccy_code, bal_dets) in new_locks {
+                Locks::insert(did, ccy_code, bal_dets);
             }
 
             let mut new_accounts = Vec::new();
@@ -101,14 +134,49 @@ pub fn migrate<T: Config>() -> frame_support::weights::Weight {
             // Update storage version.
             PalletVersion::put(StorageVersion::V3_0_0);
 
-            let count = TokenData::iter().count();
+            let count = deprecated::v4::TokenData::iter().count();
             // Return the weight consumed by the migration.
-            T::DbWeight::get().reads_writes(count as Weight + 1, count as Weight + 1)
+            Some(T::DbWeight::get().reads_writes(count as Weight + 1, count as Weight + 1))
         }
-        _ => {
-            frame_support::debug::info!(" >>> Unused migration!");
-            0
+        StorageVersion::V3_0_0 => {
+            // genesis endowments and overflowing issuance left TotalIssuance out of sync with the
+            // balances, rebuild it from the accounts
+            let totals = Module::<T>::account_totals();
+            let removed = TotalIssuance::drain().count();
+            for (ccy_code, total) in totals.iter() {
+                TotalIssuance::insert(ccy_code, total);
+            }
+
+            // Update storage version.
+            PalletVersion::put(StorageVersion::V4_0_0);
+
+            let count = Accounts::<T>::iter().count();
+            // Return the weight consumed by the migration.
+            Some(T::DbWeight::get().reads_writes(
+                (count + removed) as Weight + 1,
+                (removed + totals.len()) as Weight + 1,
+            ))
+        }
+        StorageVersion::V4_0_0 => {
+            // tokens issued so far have no supply cap
+            TokenData::translate(|_: CurrencyCode, token_data: V4TokenDetails| {
+                Some(TokenDetails {
+                    token_name: token_data.token_name,
+                    currency_code: token_data.currency_code,
+                    decimal: token_data.decimal,
+                    block_number: token_data.block_number,
+                    max_supply: None,
+                })
+            });
+
+            // Update storage version.
+            PalletVersion::put(StorageVersion::V5_0_0);
+
+            let count = TokenData::iter().count();
+            // Return the weight consumed by the migration.
+            Some(T::DbWeight::get().reads_writes(count as Weight + 1, count as Weight + 1))
         }
+        StorageVersion::V5_0_0 => None,
     }
 }
 
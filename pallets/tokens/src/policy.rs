@@ -0,0 +1,57 @@
+use super::*;
+
+/// Compliance check run on every transfer of a token
+///
+/// The runtime plugs an implementation in through `Config::TransferPolicy`, it is called by
+/// `MultiCurrency::transfer` and so covers `transfer`, `transfer_token` and every other pallet
+/// moving tokens between accounts.
+pub trait TransferPolicy {
+    /// Ensure `amount` of the token can move from the DID `from` to the DID `to`
+    fn ensure_can_transfer(
+        currency_code: CurrencyCode,
+        from: &Did,
+        to: &Did,
+        amount: TokenBalance,
+    ) -> DispatchResult;
+}
+
+impl TransferPolicy for () {
+    fn ensure_can_transfer(_: CurrencyCode, _: &Did, _: &Did, _: TokenBalance) -> DispatchResult {
+        Ok(())
+    }
+}
+
+/// Enforces the `TransferRestriction` set by the owner of each token
+///
+/// The token owner is exempt so it can always distribute and take back its token.
+pub struct RestrictionPolicy<T>(marker::PhantomData<T>);
+
+impl<T: Config> TransferPolicy for RestrictionPolicy<T> {
+    fn ensure_can_transfer(
+        currency_code: CurrencyCode,
+        from: &Did,
+        to: &Did,
+        _amount: TokenBalance,
+    ) -> DispatchResult {
+        let token_owner = Module::<T>::token_issuer(currency_code);
+        let parties = [from, to];
+        let parties = parties.iter().filter(|did| ***did != token_owner);
+        match Module::<T>::transfer_restriction(currency_code) {
+            TransferRestriction::Open => {}
+            TransferRestriction::Whitelist => {
+                for did in parties {
+                    ensure!(Module::<T>::is_whitelisted(currency_code, *did), Error::<T>::NotWhitelisted);
+                }
+            }
+            TransferRestriction::Credential(issuer) => {
+                for did in parties {
+                    ensure!(
+                        T::VcVerifier::has_active_vc(did, &vc::VCType::KycVC, &issuer),
+                        Error::<T>::MissingKycCredential
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
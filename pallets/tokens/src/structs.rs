@@ -66,6 +66,80 @@ pub struct TokenDetails {
     pub currency_code: Vec<u8>,
     pub decimal: u8,
     pub block_number: BlockNumber,
+    /// Hard cap on the total issuance, set from the TokenVC, unlimited when `None`
+    pub max_supply: Option<TokenBalance>,
+}
+
+/// Most a token can mint in each window of `period` blocks
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct MintRateLimit {
+    pub max_amount: TokenBalance,
+    pub period: BlockNumber,
+}
+
+/// Amount minted in the rate limit window starting at block `start`
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct MintWindow {
+    pub start: BlockNumber,
+    pub minted: TokenBalance,
+}
+
+/// Amount of a token that can still be minted, `None` when unlimited
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct MintableSupply {
+    /// Left before the supply cap is reached
+    pub remaining_supply: Option<TokenBalance>,
+    /// Left in the current rate limit window
+    pub remaining_in_period: Option<TokenBalance>,
+}
+
+/// Display information of a token, updatable by its owner
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct TokenMetadata {
+    /// Name shown by wallets, the `token_name` of `TokenDetails` is used when empty
+    pub display_name: Vec<u8>,
+    pub logo_uri: Vec<u8>,
+    pub website: Vec<u8>,
+    pub description: Vec<u8>,
+}
+
+/// Everything wallets need to display a token
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct TokenMetadataDetails {
+    pub details: TokenDetails,
+    pub issuer: Did,
+    pub metadata: TokenMetadata,
+}
+
+/// Who can send and receive a token
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum TransferRestriction {
+    /// Any DID
+    Open,
+    /// DIDs on the whitelist of the token
+    Whitelist,
+    /// DIDs holding an active KYC VC from the given issuer
+    Credential(Did),
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for TransferRestriction {
+    fn default() -> Self {
+        TransferRestriction::Open
+    }
+}
+
+/// An index of a redemption request. Just a `u32`.
+pub type RedemptionIndex = u32;
+
+/// Balance a holder reserved for the token owner to redeem off-chain
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct RedemptionRequest {
+    pub holder: Did,
+    pub currency_code: CurrencyCode,
+    pub amount: TokenBalance,
+    /// Identifies the redemption to the owner off-chain, e.g. a payment reference
+    pub reference: Memo,
 }
 
 /// Utility type for managing upgrades/migrations.
@@ -74,6 +148,8 @@ pub enum StorageVersion {
     V1_0_0,
     V2_0_0,
     V3_0_0,
+    V4_0_0,
+    V5_0_0,
 }
 
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
@@ -82,3 +158,12 @@ pub struct V1TokenDetails {
     pub currency_code: Vec<u8>,
     pub decimal: u8,
 }
+
+/// Token details before supply caps, storage versions 2.0.0 to 4.0.0
+#[derive(Encode, Decode, Clone, PartialEq, Eq, Default, RuntimeDebug)]
+pub struct V4TokenDetails {
+    pub token_name: Vec<u8>,
+    pub currency_code: Vec<u8>,
+    pub decimal: u8,
+    pub block_number: BlockNumber,
+}
@@ -5,13 +5,14 @@ use crate::{self as tokens, Config};
 use balances;
 use did;
 use frame_support::{
-    assert_noop, assert_ok, ord_parameter_types, parameter_types, traits::StorageMapShim,
+    assert_noop, assert_ok, ord_parameter_types, parameter_types,
+    traits::{OnRuntimeUpgrade, StorageMapShim},
 };
 use frame_system::EnsureSignedBy;
 use sp_core::{sr25519, Pair, H256};
 use sp_runtime::{
     testing::Header,
-    traits::{BlakeTwo256, Hash, IdentityLookup},
+    traits::{BlakeTwo256, Dispatchable, Hash, IdentityLookup},
     Perbill,
 };
 use validator_set;
@@ -97,6 +98,8 @@ impl balances::Config for Test {
 parameter_types! {
     // the minimum reserve amount required to create a new token
     pub const TreasuryReserveAmount: Balance = TREASURY_RESERVE_AMOUNT as u64; //10 million MUI - consider 6decimal places
+    pub const MaxBatchTransfers: u32 = 3;
+    pub const MaxTokenMetadataLength: u32 = 32;
 }
 
 ord_parameter_types! {
@@ -110,11 +113,26 @@ impl Config for Test {
     type WeightInfo = ();
     type Currency = Balances;
     type TreasuryReserve = TreasuryReserveAmount;
+    type VcVerifier = VC;
+    type MaxLocks = MaxLocks;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type MaxTokenMetadataLength = MaxTokenMetadataLength;
+    type TransferPolicy = RestrictionPolicy<Test>;
+}
+
+parameter_types! {
+    pub const VCDeposit: Balance = 100;
+    pub const VCTombstoneDeposit: Balance = 10;
+    pub const VCMaxAge: u64 = 100;
 }
 
 impl vc::Config for Test {
     type Event = Event;
     type ApproveOrigin = EnsureSignedBy<CouncilElectedUser, u64>;
+    type Currency = Balances;
+    type VCDeposit = VCDeposit;
+    type VCTombstoneDeposit = VCTombstoneDeposit;
+    type VCMaxAge = VCMaxAge;
 }
 
 impl did::Config for Test {
@@ -151,34 +169,48 @@ const DAVE_SEED: [u8; 32] = [
     225, 20, 83, 97, 40, 0, 182, 99, 48, 114, 70,
 ];
 
+/// Currency endowed to BOB at genesis by the total issuance tests, without a registered token
+const ENDOWED_CURRENCY: CurrencyCode = *b"END\0\0\0\0\0";
+
 pub struct ExtBuilder {
-    endowed_accounts: Vec<(did::Did, CurrencyId, Balance)>,
+    endowed_accounts: Vec<(did::Did, CurrencyCode, Balance)>,
     treasury_genesis: bool,
+    genesis_vcs: Vec<vc::VCHash>,
+    issued_tokens: Vec<(vc::VCid, TokenBalance)>,
 }
 
 impl Default for ExtBuilder {
     fn default() -> Self {
         Self {
-            endowed_accounts: vec![],
+            endowed_accounts: vec![(BOB, convert_to_array::<8>("OTH".into()), INITIAL_BALANCE)],
             treasury_genesis: false,
+            genesis_vcs: vec![],
+            issued_tokens: vec![],
         }
     }
 }
 
 impl ExtBuilder {
+    pub fn balances(mut self, endowed_accounts: Vec<(did::Did, CurrencyCode, Balance)>) -> Self {
+        self.endowed_accounts = endowed_accounts;
+        self
+    }
+
+    pub fn genesis_tokens(
+        mut self,
+        genesis_vcs: Vec<vc::VCHash>,
+        issued_tokens: Vec<(vc::VCid, TokenBalance)>,
+    ) -> Self {
+        self.genesis_vcs = genesis_vcs;
+        self.issued_tokens = issued_tokens;
+        self
+    }
+
     pub fn build(self) -> sp_io::TestExternalities {
         let mut t = frame_system::GenesisConfig::default()
             .build_storage::<Test>()
             .unwrap();
 
-        let currency_code = convert_to_array::<8>("OTH".into());
-
-        super::GenesisConfig {
-            endowed_accounts: vec![(BOB, currency_code, INITIAL_BALANCE.into())],
-        }
-        .assimilate_storage::<Test>(&mut t)
-        .unwrap();
-
         did::GenesisConfig {
             dids: vec![
                 DidStruct {
@@ -217,6 +249,23 @@ impl ExtBuilder {
         .assimilate_storage(&mut t)
         .unwrap();
 
+        vc::GenesisConfig {
+            init_vcs: self.genesis_vcs,
+        }
+        .assimilate_storage::<Test>(&mut t)
+        .unwrap();
+
+        super::GenesisConfig {
+            endowed_accounts: self
+                .endowed_accounts
+                .into_iter()
+                .map(|(did, currency_code, balance)| (did, currency_code, balance.into()))
+                .collect(),
+            issued_tokens: self.issued_tokens,
+        }
+        .assimilate_storage::<Test>(&mut t)
+        .unwrap();
+
         let mut ext = sp_io::TestExternalities::new(t);
         ext.execute_with(|| System::set_block_number(1));
         ext
@@ -234,6 +283,76 @@ fn convert_to_array<const N: usize>(mut v: Vec<u8>) -> [u8; N] {
     })
 }
 
+/// Store a TokenVC for a new token owned by BOB, issued by ALICE
+fn store_test_token_vc(currency_code: CurrencyCode, max_supply: Option<TokenBalance>) -> vc::VCid {
+    let token_vc = vc::TokenVC {
+        token_name: convert_to_array::<16>("test".into()),
+        reservable_balance: 1_000_000,
+        decimal: 6,
+        currency_code,
+        max_supply,
+    };
+
+    let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+    let vc_type = vc::VCType::TokenVC;
+    let pair: sr25519::Pair = sr25519::Pair::from_seed(&ALICE_SEED);
+    let owner = BOB;
+    let issuers = vec![ALICE];
+    let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+    let signature = pair.sign(hash.as_ref());
+
+    let vc_struct: vc::VC<H256> = vc::VC {
+        hash,
+        signatures: vec![signature],
+        vc_type,
+        owner,
+        issuers,
+        is_vc_used: false,
+        vc_property: token_vc,
+    };
+    let vc_id = *BlakeTwo256::hash_of(&vc_struct).as_fixed_bytes();
+
+    assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc_struct.encode()));
+    vc_id
+}
+
+/// Issue `amount` of a new token owned by BOB, backed by a TokenVC issued by ALICE
+fn issue_test_token(currency_code: CurrencyCode, amount: TokenBalance) {
+    let vc_id = store_test_token_vc(currency_code, None);
+    assert_ok!(Tokens::issue_token(Origin::signed(BOB_ACCOUNT_ID), vc_id, amount));
+}
+
+/// Store a MintTokens VC owned by DAVE for the token issued with `token_vc_id`
+fn store_mint_vc(token_vc_id: vc::VCid, currency_code: CurrencyCode, amount: TokenBalance) -> vc::VCid {
+    let mint_vc = vc::SlashMintTokens {
+        vc_id: token_vc_id,
+        currency_code,
+        amount,
+    };
+
+    let mint_vc: [u8; 128] = convert_to_array::<128>(mint_vc.encode());
+    let vc_type = vc::VCType::MintTokens;
+    let pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+    let owner = DAVE;
+    let issuers = vec![BOB];
+    let hash = BlakeTwo256::hash_of(&(&vc_type, &mint_vc, &owner, &issuers));
+    let signature = pair.sign(hash.as_ref());
+
+    let vc_struct: vc::VC<H256> = vc::VC {
+        hash,
+        signatures: vec![signature],
+        vc_type,
+        owner,
+        issuers,
+        is_vc_used: false,
+        vc_property: mint_vc,
+    };
+    let vc_id = *BlakeTwo256::hash_of(&vc_struct).as_fixed_bytes();
+
+    assert_ok!(VC::store(Origin::signed(DAVE_ACCOUNT_ID), vc_struct.encode()));
+    vc_id
+}
+
 #[test]
 fn genesis_config_works() {
     ExtBuilder::default().build().execute_with(|| {
@@ -247,6 +366,71 @@ fn genesis_config_works() {
     });
 }
 
+#[test]
+fn genesis_token_issuance_works() {
+    let reservable_balance: u128 = 1000000;
+    let currency_code: CurrencyCode = convert_to_array::<8>("GEN".into());
+    let token_vc = vc::TokenVC {
+        token_name: convert_to_array::<16>("genesis".into()),
+        reservable_balance,
+        decimal: 6,
+        currency_code,
+        max_supply: None,
+    };
+
+    let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+    let vc_type = vc::VCType::TokenVC;
+    let pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+    let owner = BOB;
+    let issuers = vec![BOB];
+    let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+    let signature = pair.sign(hash.as_ref());
+
+    let vc_struct: vc::VC<H256> = vc::VC {
+        hash,
+        signatures: vec![signature],
+        vc_type,
+        owner,
+        issuers,
+        is_vc_used: false,
+        vc_property: token_vc,
+    };
+    let vc_id = *BlakeTwo256::hash_of(&vc_struct).as_fixed_bytes();
+    let token_amount: u128 = 5_000_000;
+
+    ExtBuilder::default()
+        .genesis_tokens(vec![vc_struct.encode()], vec![(vc_id, token_amount)])
+        .build()
+        .execute_with(|| {
+            assert_eq!(Tokens::total_issuance(currency_code), token_amount);
+            assert_eq!(
+                Tokens::free_balance(TEST_TOKEN_ID, &BOB_ACCOUNT_ID),
+                token_amount
+            );
+            assert_eq!(Tokens::token_issuer(currency_code), BOB);
+            assert_eq!(
+                Tokens::token_data(currency_code).unwrap(),
+                TokenDetails {
+                    token_name: "genesis".into(),
+                    currency_code: "GEN".into(),
+                    decimal: 6,
+                    block_number: 0,
+                    max_supply: None,
+                }
+            );
+            assert_eq!(
+                Balances::reserved_balance(BOB_ACCOUNT_ID),
+                reservable_balance as u64
+            );
+
+            // the backing TokenVC can't be used again
+            assert_noop!(
+                Tokens::issue_token(Origin::signed(BOB_ACCOUNT_ID), vc_id, token_amount),
+                vc::Error::<Test>::VCAlreadyUsed
+            );
+        });
+}
+
 #[test]
 fn only_vc_owner_can_issue_token() {
     ExtBuilder::default().build().execute_with(|| {
@@ -255,6 +439,7 @@ fn only_vc_owner_can_issue_token() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -292,7 +477,7 @@ fn only_vc_owner_can_issue_token() {
 
 #[test]
 fn issue_token_works() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let reservable_balance: u128 = 1000000;
         let currency_code: CurrencyCode = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
@@ -300,6 +485,7 @@ fn issue_token_works() {
             reservable_balance: reservable_balance,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -354,6 +540,7 @@ fn issue_token_works() {
                 currency_code: "OTH".into(),
                 decimal: 6,
                 block_number: 1,
+                max_supply: None,
             }
         );
 
@@ -379,7 +566,7 @@ fn issue_token_works() {
 
 #[test]
 fn test_transfer_token_works() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
 
         let token_vc = vc::TokenVC {
@@ -387,6 +574,7 @@ fn test_transfer_token_works() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -456,7 +644,7 @@ fn test_transfer_token_works() {
 
 #[test]
 fn test_withdraw_reserve_works() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let reservable_balance: u128 = 1000000;
         let currency_code: CurrencyCode = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
@@ -464,6 +652,7 @@ fn test_withdraw_reserve_works() {
             reservable_balance: reservable_balance,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -531,6 +720,7 @@ fn test_withdraw_reserve_works() {
                 currency_code: "OTH".into(),
                 decimal: 6,
                 block_number: 1,
+                max_supply: None,
             }
         );
 
@@ -560,13 +750,14 @@ fn test_get_currency_id() {
 
 #[test]
 fn test_slash_token() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code: CurrencyCode = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -651,13 +842,14 @@ fn test_slash_token() {
 
 #[test]
 fn test_mint_token() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code: CurrencyCode = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -746,13 +938,14 @@ fn test_mint_token() {
 
 #[test]
 fn test_transfer_token() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code: CurrencyCode = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -849,13 +1042,14 @@ fn test_transfer_token() {
 
 #[test]
 fn test_decimal_and_ccy_code() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -905,6 +1099,7 @@ fn test_ccy_code_exists() {
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -945,6 +1140,7 @@ fn test_ccy_code_exists() {
             reservable_balance: 2_000_000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -982,13 +1178,14 @@ fn test_ccy_code_exists() {
 
 #[test]
 fn test_set_balance() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -1056,13 +1253,14 @@ fn test_set_balance() {
 
 #[test]
 fn test_set_whole_balance() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -1129,13 +1327,14 @@ fn test_set_whole_balance() {
 
 #[test]
 fn test_set_balance_greater_amount() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -1191,13 +1390,14 @@ fn test_set_balance_greater_amount() {
 
 #[test]
 fn test_set_balance_less_than_existing() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -1273,13 +1473,14 @@ fn test_set_balance_less_than_existing() {
 
 #[test]
 fn test_set_balance_zero() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -1357,13 +1558,14 @@ fn test_set_balance_zero() {
 
 #[test]
 fn test_set_balance_token_owner() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -1418,13 +1620,14 @@ fn test_set_balance_token_owner() {
 
 #[test]
 fn test_set_balance_not_token_owner() {
-    ExtBuilder::default().build().execute_with(|| {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
         let currency_code = convert_to_array::<8>("OTH".into());
         let token_vc = vc::TokenVC {
             token_name: convert_to_array::<16>("test".into()),
             reservable_balance: 1_000_000,
             decimal: 6,
             currency_code,
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -1475,4 +1678,930 @@ fn test_set_balance_not_token_owner() {
             token_amount
         );
     });
-}
\ No newline at end of file
+}
+#[test]
+fn test_set_metadata() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 5_000_000);
+
+        let metadata = Tokens::get_token_metadata(currency_code).unwrap();
+        assert_eq!(metadata.issuer, BOB);
+        assert_eq!(metadata.details.decimal, 6);
+        assert_eq!(metadata.metadata, TokenMetadata::default());
+
+        assert_ok!(Tokens::set_metadata(
+            Origin::signed(BOB_ACCOUNT_ID),
+            currency_code,
+            b"Other Token".to_vec(),
+            b"ipfs://logo".to_vec(),
+            b"https://oth.example".to_vec(),
+            b"A test token".to_vec(),
+        ));
+        assert_eq!(
+            Tokens::token_metadata(currency_code),
+            Some(TokenMetadata {
+                display_name: b"Other Token".to_vec(),
+                logo_uri: b"ipfs://logo".to_vec(),
+                website: b"https://oth.example".to_vec(),
+                description: b"A test token".to_vec(),
+            })
+        );
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::TokenMetadataUpdated(currency_code, BOB))
+        );
+
+        // only the owner can update the metadata, within the bounds
+        assert_noop!(
+            Tokens::set_metadata(
+                Origin::signed(DAVE_ACCOUNT_ID),
+                currency_code,
+                b"Fake".to_vec(),
+                vec![],
+                vec![],
+                vec![],
+            ),
+            Error::<Test>::NotAllowed
+        );
+        assert_noop!(
+            Tokens::set_metadata(
+                Origin::signed(BOB_ACCOUNT_ID),
+                currency_code,
+                vec![],
+                vec![],
+                vec![],
+                vec![0; 33],
+            ),
+            Error::<Test>::MetadataTooLong
+        );
+        assert_noop!(
+            Tokens::set_metadata(
+                Origin::signed(BOB_ACCOUNT_ID),
+                convert_to_array::<8>("NONE".into()),
+                vec![],
+                vec![],
+                vec![],
+                vec![],
+            ),
+            Error::<Test>::TokenNotFound
+        );
+    });
+}
+
+#[test]
+fn test_pause_token() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 5_000_000);
+
+        assert_noop!(
+            Tokens::pause(Origin::signed(DAVE_ACCOUNT_ID), currency_code),
+            Error::<Test>::NotAllowed
+        );
+        assert_ok!(Tokens::pause(Origin::signed(BOB_ACCOUNT_ID), currency_code));
+        assert!(Tokens::is_paused(currency_code));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::TokenPaused(currency_code))
+        );
+
+        assert_noop!(
+            Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 100),
+            Error::<Test>::TokenIsPaused
+        );
+        assert_noop!(
+            <Tokens as MultiCurrency<_>>::withdraw(TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 100),
+            Error::<Test>::TokenIsPaused
+        );
+
+        // root can lift the pause as well
+        assert_ok!(Tokens::unpause(Origin::root(), currency_code));
+        assert!(!Tokens::is_paused(currency_code));
+        assert_ok!(Tokens::transfer(
+            Origin::signed(BOB_ACCOUNT_ID),
+            DAVE_ACCOUNT_ID,
+            currency_code,
+            100
+        ));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 100);
+    });
+}
+
+#[test]
+fn test_freeze_account() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 5_000_000);
+        assert_ok!(Tokens::transfer(
+            Origin::signed(BOB_ACCOUNT_ID),
+            DAVE_ACCOUNT_ID,
+            currency_code,
+            1_000
+        ));
+
+        assert_noop!(
+            Tokens::freeze(Origin::signed(DAVE_ACCOUNT_ID), currency_code, DAVE),
+            Error::<Test>::NotAllowed
+        );
+        assert_ok!(Tokens::freeze(Origin::signed(BOB_ACCOUNT_ID), currency_code, DAVE));
+        assert!(Tokens::is_frozen(DAVE, currency_code));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::AccountFrozen(currency_code, DAVE))
+        );
+
+        assert_noop!(
+            Tokens::transfer(Origin::signed(DAVE_ACCOUNT_ID), BOB_ACCOUNT_ID, currency_code, 100),
+            Error::<Test>::AccountIsFrozen
+        );
+        // other holders are not affected
+        assert_ok!(Tokens::transfer(
+            Origin::signed(BOB_ACCOUNT_ID),
+            DAVE_ACCOUNT_ID,
+            currency_code,
+            100
+        ));
+
+        assert_ok!(Tokens::thaw(Origin::root(), currency_code, DAVE));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::AccountThawed(currency_code, DAVE))
+        );
+        assert_ok!(Tokens::transfer(
+            Origin::signed(DAVE_ACCOUNT_ID),
+            BOB_ACCOUNT_ID,
+            currency_code,
+            100
+        ));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 1_000);
+    });
+}
+
+const ID_1: LockIdentifier = *b"1       ";
+const ID_2: LockIdentifier = *b"2       ";
+
+#[test]
+fn basic_locking_should_work() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_ok!(Tokens::set_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 900));
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 900);
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::LockSet(currency_code, BOB, ID_1, 900))
+        );
+
+        assert_noop!(
+            Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 101),
+            Error::<Test>::LiquidityRestrictions
+        );
+        assert!(!Tokens::can_reserve(TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 101));
+        assert_ok!(Tokens::transfer(
+            Origin::signed(BOB_ACCOUNT_ID),
+            DAVE_ACCOUNT_ID,
+            currency_code,
+            100
+        ));
+    });
+}
+
+#[test]
+fn lock_replacement_and_overlap_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        // setting the same id replaces the lock
+        assert_ok!(Tokens::set_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 900));
+        assert_ok!(Tokens::set_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 500));
+        assert_eq!(Tokens::locks(BOB, currency_code).len(), 1);
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 500);
+
+        // locks with different ids overlap, the largest one applies
+        assert_ok!(Tokens::set_lock(ID_2, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 700));
+        assert_eq!(Tokens::locks(BOB, currency_code).len(), 2);
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 700);
+    });
+}
+
+#[test]
+fn lock_value_extension_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_ok!(Tokens::set_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 500));
+        // extending never lowers a lock
+        assert_ok!(Tokens::extend_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 200));
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 500);
+        assert_ok!(Tokens::extend_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 800));
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 800);
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::LockSet(currency_code, BOB, ID_1, 800))
+        );
+
+        // extending a missing lock creates it
+        assert_ok!(Tokens::extend_lock(ID_2, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 100));
+        assert_eq!(Tokens::locks(BOB, currency_code).len(), 2);
+    });
+}
+
+#[test]
+fn lock_removal_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_ok!(Tokens::set_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 900));
+        assert_ok!(Tokens::set_lock(ID_2, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 300));
+        assert_ok!(Tokens::remove_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID));
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 300);
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::LockRemoved(currency_code, BOB, ID_1))
+        );
+
+        assert_ok!(Tokens::remove_lock(ID_2, TEST_TOKEN_ID, &BOB_ACCOUNT_ID));
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 0);
+        assert!(!Locks::contains_key(BOB, currency_code));
+        assert_ok!(Tokens::transfer(
+            Origin::signed(BOB_ACCOUNT_ID),
+            DAVE_ACCOUNT_ID,
+            currency_code,
+            1_000
+        ));
+    });
+}
+
+#[test]
+fn lock_follows_did_across_key_rotation() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_ok!(Tokens::set_lock(ID_1, TEST_TOKEN_ID, &BOB_ACCOUNT_ID, 900));
+
+        // rotate BOB's key, the lock stays with the did
+        let new_public_key = sr25519::Pair::from_seed(&[7; 32]).public();
+        assert_ok!(Did::rotate_key(Origin::signed(ALICE_ACCOUNT_ID), BOB, new_public_key));
+        let new_account_id = did::Module::<Test>::get_accountid_from_pubkey(&new_public_key);
+        assert_eq!(Tokens::locks(BOB, currency_code).len(), 1);
+        assert_noop!(
+            Tokens::transfer(Origin::signed(new_account_id), DAVE_ACCOUNT_ID, currency_code, 101),
+            Error::<Test>::LiquidityRestrictions
+        );
+
+        // the new key can remove the lock set under the old one
+        assert_ok!(Tokens::remove_lock(ID_1, TEST_TOKEN_ID, &new_account_id));
+        assert_eq!(Tokens::accounts(BOB, currency_code).data.frozen, 0);
+        assert!(!Locks::contains_key(BOB, currency_code));
+        assert_ok!(Tokens::transfer(
+            Origin::signed(new_account_id),
+            DAVE_ACCOUNT_ID,
+            currency_code,
+            1_000
+        ));
+    });
+}
+
+#[test]
+fn test_approve_and_transfer_from() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_ok!(Tokens::approve(Origin::signed(BOB_ACCOUNT_ID), DAVE, currency_code, 300));
+        assert_eq!(Tokens::allowance((BOB, DAVE), currency_code), 300);
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::Approval(currency_code, BOB, DAVE, 300))
+        );
+
+        assert_noop!(
+            Tokens::transfer_from(Origin::signed(DAVE_ACCOUNT_ID), BOB, ALICE_ACCOUNT_ID, currency_code, 301),
+            Error::<Test>::InsufficientAllowance
+        );
+        assert_ok!(Tokens::transfer_from(
+            Origin::signed(DAVE_ACCOUNT_ID),
+            BOB,
+            ALICE_ACCOUNT_ID,
+            currency_code,
+            200
+        ));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &ALICE_ACCOUNT_ID), 200);
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &BOB_ACCOUNT_ID), 800);
+        assert_eq!(Tokens::allowance((BOB, DAVE), currency_code), 100);
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::Transferred(currency_code, BOB, ALICE, 200))
+        );
+
+        // the allowance doesn't let the spender bypass the owner's restrictions
+        assert_ok!(Tokens::freeze(Origin::root(), currency_code, BOB));
+        assert_noop!(
+            Tokens::transfer_from(Origin::signed(DAVE_ACCOUNT_ID), BOB, ALICE_ACCOUNT_ID, currency_code, 100),
+            Error::<Test>::AccountIsFrozen
+        );
+
+        // a spent allowance is removed
+        assert_ok!(Tokens::thaw(Origin::root(), currency_code, BOB));
+        assert_ok!(Tokens::transfer_from(
+            Origin::signed(DAVE_ACCOUNT_ID),
+            BOB,
+            DAVE_ACCOUNT_ID,
+            currency_code,
+            100
+        ));
+        assert!(!Allowances::contains_key((BOB, DAVE), currency_code));
+    });
+}
+
+#[test]
+fn test_adjust_allowance() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_noop!(
+            Tokens::approve(Origin::signed(BOB_ACCOUNT_ID), [1; 32], currency_code, 300),
+            Error::<Test>::SpenderDIDNotRegistered
+        );
+        assert_noop!(
+            Tokens::approve(
+                Origin::signed(BOB_ACCOUNT_ID),
+                DAVE,
+                convert_to_array::<8>("NONE".into()),
+                300
+            ),
+            Error::<Test>::TokenNotFound
+        );
+
+        assert_ok!(Tokens::increase_allowance(Origin::signed(BOB_ACCOUNT_ID), DAVE, currency_code, 300));
+        assert_ok!(Tokens::increase_allowance(Origin::signed(BOB_ACCOUNT_ID), DAVE, currency_code, 200));
+        assert_eq!(Tokens::allowance((BOB, DAVE), currency_code), 500);
+        assert_noop!(
+            Tokens::increase_allowance(Origin::signed(BOB_ACCOUNT_ID), DAVE, currency_code, TokenBalance::MAX),
+            Error::<Test>::BalanceOverflow
+        );
+
+        assert_ok!(Tokens::decrease_allowance(Origin::signed(BOB_ACCOUNT_ID), DAVE, currency_code, 100));
+        assert_eq!(Tokens::allowance((BOB, DAVE), currency_code), 400);
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::Approval(currency_code, BOB, DAVE, 400))
+        );
+        assert_noop!(
+            Tokens::decrease_allowance(Origin::signed(BOB_ACCOUNT_ID), DAVE, currency_code, 401),
+            Error::<Test>::InsufficientAllowance
+        );
+
+        // approve replaces the allowance
+        assert_ok!(Tokens::approve(Origin::signed(BOB_ACCOUNT_ID), DAVE, currency_code, 50));
+        assert_eq!(Tokens::allowance((BOB, DAVE), currency_code), 50);
+    });
+}
+
+#[test]
+fn test_batch_transfer() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+        let memo: balances::Memo = b"salary".to_vec().into();
+
+        assert_ok!(Tokens::batch_transfer(
+            Origin::signed(BOB_ACCOUNT_ID),
+            currency_code,
+            vec![
+                (ALICE_ACCOUNT_ID, 100, Some(memo.clone())),
+                (DAVE_ACCOUNT_ID, 200, None),
+            ],
+            false
+        ));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &ALICE_ACCOUNT_ID), 100);
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 200);
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &BOB_ACCOUNT_ID), 700);
+
+        let events: Vec<_> = System::events().into_iter().map(|record| record.event).collect();
+        assert!(events.contains(&Event::tokens(crate::Event::TransferredWithMemo(
+            currency_code,
+            BOB,
+            ALICE,
+            100,
+            Some(memo)
+        ))));
+        assert_eq!(
+            events.last(),
+            Some(&Event::tokens(crate::Event::TransferredWithMemo(currency_code, BOB, DAVE, 200, None)))
+        );
+    });
+}
+
+#[test]
+fn test_batch_transfer_is_all_or_nothing() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        // the last transfer exceeds the remaining balance
+        assert_noop!(
+            Tokens::batch_transfer(
+                Origin::signed(BOB_ACCOUNT_ID),
+                currency_code,
+                vec![(ALICE_ACCOUNT_ID, 600, None), (DAVE_ACCOUNT_ID, 600, None)],
+                false
+            ),
+            Error::<Test>::BalanceTooLow
+        );
+        assert_noop!(
+            Tokens::batch_transfer(
+                Origin::signed(BOB_ACCOUNT_ID),
+                currency_code,
+                vec![(ALICE_ACCOUNT_ID, 100, Some(vec![0; 129].into()))],
+                false
+            ),
+            Error::<Test>::InvalidMemoLength
+        );
+        assert_noop!(
+            Tokens::batch_transfer(
+                Origin::signed(BOB_ACCOUNT_ID),
+                currency_code,
+                vec![(ALICE_ACCOUNT_ID, 1, None); 4],
+                false
+            ),
+            Error::<Test>::TooManyTransfers
+        );
+    });
+}
+
+#[test]
+fn test_batch_transfer_best_effort() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_ok!(Tokens::batch_transfer(
+            Origin::signed(BOB_ACCOUNT_ID),
+            currency_code,
+            vec![
+                (ALICE_ACCOUNT_ID, 600, None),
+                (DAVE_ACCOUNT_ID, 600, None),
+                (DAVE_ACCOUNT_ID, 400, None),
+            ],
+            true
+        ));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &ALICE_ACCOUNT_ID), 600);
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 400);
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &BOB_ACCOUNT_ID), 0);
+
+        let events: Vec<_> = System::events().into_iter().map(|record| record.event).collect();
+        // the message of the error is not stored with the event
+        assert!(events.contains(&Event::tokens(crate::Event::BatchTransferFailed(
+            currency_code,
+            1,
+            DispatchError::from(Error::<Test>::BalanceTooLow).stripped()
+        ))));
+    });
+}
+
+#[test]
+fn test_total_issuance_invariant() {
+    ExtBuilder::default().balances(vec![(BOB, ENDOWED_CURRENCY, INITIAL_BALANCE)]).build().execute_with(|| {
+        // genesis endowments are part of the issuance
+        assert_eq!(Tokens::total_issuance(ENDOWED_CURRENCY), INITIAL_BALANCE as TokenBalance);
+        assert_ok!(Tokens::check_total_issuance());
+
+        // issuing a token over an endowed currency code adds to the owner's balance
+        issue_test_token(ENDOWED_CURRENCY, 1_000);
+        assert_eq!(Tokens::total_issuance(ENDOWED_CURRENCY), INITIAL_BALANCE as TokenBalance + 1_000);
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &BOB_ACCOUNT_ID), INITIAL_BALANCE as TokenBalance + 1_000);
+        assert_ok!(Tokens::check_total_issuance());
+
+        assert_ok!(Tokens::set_balance(Origin::root(), ALICE, ENDOWED_CURRENCY, 300));
+        assert_ok!(Tokens::transfer(Origin::signed(ALICE_ACCOUNT_ID), DAVE_ACCOUNT_ID, ENDOWED_CURRENCY, 100));
+        assert_ok!(<Tokens as MultiReservableCurrency<u64>>::reserve(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID, 50));
+        assert_ok!(Tokens::check_total_issuance());
+
+        TotalIssuance::insert(ENDOWED_CURRENCY, 1);
+        assert!(Tokens::check_total_issuance().is_err());
+    });
+}
+
+#[test]
+fn test_issue_token_overflow() {
+    ExtBuilder::default().balances(vec![(BOB, ENDOWED_CURRENCY, INITIAL_BALANCE)]).build().execute_with(|| {
+        let vc_id = store_test_token_vc(ENDOWED_CURRENCY, None);
+        assert_noop!(
+            Tokens::issue_token(Origin::signed(BOB_ACCOUNT_ID), vc_id, TokenBalance::MAX),
+            Error::<Test>::TotalIssuanceOverflow
+        );
+        assert_eq!(Tokens::total_issuance(ENDOWED_CURRENCY), INITIAL_BALANCE as TokenBalance);
+    });
+}
+
+#[test]
+fn test_migrate_total_issuance() {
+    ExtBuilder::default().balances(vec![(BOB, ENDOWED_CURRENCY, INITIAL_BALANCE)]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        // issuance out of sync with the balances, and issuance of a currency nobody holds
+        TotalIssuance::insert(ENDOWED_CURRENCY, 0);
+        TotalIssuance::insert(currency_code, 5);
+        TotalIssuance::insert(*b"NONE\0\0\0\0", 10);
+        PalletVersion::put(StorageVersion::V3_0_0);
+
+        migration::migrate::<Test>();
+        assert_eq!(PalletVersion::get(), StorageVersion::V5_0_0);
+        assert_eq!(Tokens::total_issuance(ENDOWED_CURRENCY), INITIAL_BALANCE as TokenBalance);
+        assert_eq!(Tokens::total_issuance(currency_code), 1_000);
+        assert!(!TotalIssuance::contains_key(*b"NONE\0\0\0\0"));
+        assert_ok!(Tokens::check_total_issuance());
+    });
+}
+
+#[test]
+fn test_supply_cap() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        let token_vc_id = store_test_token_vc(currency_code, Some(1_500));
+        assert_noop!(
+            Tokens::issue_token(Origin::signed(BOB_ACCOUNT_ID), token_vc_id, 1_501),
+            Error::<Test>::SupplyCapExceeded
+        );
+        assert_ok!(Tokens::issue_token(Origin::signed(BOB_ACCOUNT_ID), token_vc_id, 1_000));
+        assert_eq!(Tokens::token_data(currency_code).unwrap().max_supply, Some(1_500));
+        assert_eq!(
+            Tokens::mintable_supply(currency_code),
+            Some(MintableSupply {
+                remaining_supply: Some(500),
+                remaining_in_period: None,
+            })
+        );
+
+        let mint_vc_id = store_mint_vc(token_vc_id, currency_code, 501);
+        assert_noop!(
+            Tokens::mint_token(Origin::signed(DAVE_ACCOUNT_ID), mint_vc_id),
+            Error::<Test>::SupplyCapExceeded
+        );
+        let mint_vc_id = store_mint_vc(token_vc_id, currency_code, 500);
+        assert_ok!(Tokens::mint_token(Origin::signed(DAVE_ACCOUNT_ID), mint_vc_id));
+        assert_eq!(Tokens::total_issuance(currency_code), 1_500);
+        assert_eq!(Tokens::mintable_supply(currency_code).unwrap().remaining_supply, Some(0));
+        assert_eq!(Tokens::mintable_supply(*b"NONE\0\0\0\0"), None);
+    });
+}
+
+#[test]
+fn test_mint_rate_limit() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        let token_vc_id = store_test_token_vc(currency_code, None);
+        assert_ok!(Tokens::issue_token(Origin::signed(BOB_ACCOUNT_ID), token_vc_id, 1_000));
+
+        let limit = MintRateLimit {
+            max_amount: 100,
+            period: 10,
+        };
+        assert_noop!(
+            Tokens::set_mint_rate_limit(Origin::signed(DAVE_ACCOUNT_ID), currency_code, Some(limit)),
+            Error::<Test>::NotAllowed
+        );
+        assert_noop!(
+            Tokens::set_mint_rate_limit(
+                Origin::signed(BOB_ACCOUNT_ID),
+                currency_code,
+                Some(MintRateLimit { max_amount: 100, period: 0 })
+            ),
+            Error::<Test>::ZeroMintPeriod
+        );
+        assert_ok!(Tokens::set_mint_rate_limit(Origin::signed(BOB_ACCOUNT_ID), currency_code, Some(limit)));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::MintRateLimitSet(currency_code, Some(limit)))
+        );
+
+        let mint_vc_id = store_mint_vc(token_vc_id, currency_code, 60);
+        assert_ok!(Tokens::mint_token(Origin::signed(DAVE_ACCOUNT_ID), mint_vc_id));
+        assert_eq!(Tokens::mintable_supply(currency_code).unwrap().remaining_in_period, Some(40));
+
+        let mint_vc_id = store_mint_vc(token_vc_id, currency_code, 50);
+        assert_noop!(
+            Tokens::mint_token(Origin::signed(DAVE_ACCOUNT_ID), mint_vc_id),
+            Error::<Test>::MintRateLimitExceeded
+        );
+
+        // a new period starts at block 10
+        System::set_block_number(10);
+        assert_eq!(Tokens::mintable_supply(currency_code).unwrap().remaining_in_period, Some(100));
+        assert_ok!(Tokens::mint_token(Origin::signed(DAVE_ACCOUNT_ID), mint_vc_id));
+        assert_eq!(Tokens::total_issuance(currency_code), 1_110);
+
+        assert_ok!(Tokens::set_mint_rate_limit(Origin::root(), currency_code, None));
+        assert_eq!(Tokens::mint_rate_limit(currency_code), None);
+        assert_eq!(Tokens::mintable_supply(currency_code).unwrap().remaining_in_period, None);
+    });
+}
+
+#[test]
+fn test_migrate_token_details() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        migration::deprecated::v4::TokenData::insert(
+            currency_code,
+            V4TokenDetails {
+                token_name: "test".into(),
+                currency_code: "OTH".into(),
+                decimal: 6,
+                block_number: 1,
+            },
+        );
+        PalletVersion::put(StorageVersion::V4_0_0);
+
+        migration::migrate::<Test>();
+        assert_eq!(PalletVersion::get(), StorageVersion::V5_0_0);
+        assert_eq!(
+            Tokens::token_data(currency_code),
+            Some(TokenDetails {
+                token_name: "test".into(),
+                currency_code: "OTH".into(),
+                decimal: 6,
+                block_number: 1,
+                max_supply: None,
+            })
+        );
+    });
+}
+
+#[test]
+fn test_burn() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_ok!(Tokens::burn(Origin::signed(BOB_ACCOUNT_ID), currency_code, 400));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::TokenBurned(currency_code, BOB, 400))
+        );
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &BOB_ACCOUNT_ID), 600);
+        assert_eq!(Tokens::total_issuance(currency_code), 600);
+
+        assert_noop!(
+            Tokens::burn(Origin::signed(BOB_ACCOUNT_ID), currency_code, 601),
+            Error::<Test>::BalanceTooLow
+        );
+        assert_noop!(
+            Tokens::burn(Origin::signed(BOB_ACCOUNT_ID), *b"NONE\0\0\0\0", 1),
+            Error::<Test>::TokenNotFound
+        );
+        assert_ok!(Tokens::check_total_issuance());
+    });
+}
+
+#[test]
+fn test_redemption() {
+    ExtBuilder::default().balances(vec![]).build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+        assert_ok!(Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 500));
+        let reference: balances::Memo = b"IBAN DE00 1234".to_vec().into();
+
+        assert_noop!(
+            Tokens::request_redemption(Origin::signed(DAVE_ACCOUNT_ID), currency_code, 100, vec![0; 129].into()),
+            Error::<Test>::InvalidMemoLength
+        );
+        assert_ok!(Tokens::request_redemption(
+            Origin::signed(DAVE_ACCOUNT_ID),
+            currency_code,
+            200,
+            reference.clone()
+        ));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::RedemptionRequested(0, currency_code, DAVE, 200, reference.clone()))
+        );
+        assert_ok!(Tokens::request_redemption(
+            Origin::signed(DAVE_ACCOUNT_ID),
+            currency_code,
+            100,
+            reference
+        ));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 200);
+        assert_eq!(Tokens::reserved_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 300);
+
+        // only the token owner or root settles the request
+        assert_noop!(
+            Tokens::confirm_redemption(Origin::signed(DAVE_ACCOUNT_ID), 0),
+            Error::<Test>::NotAllowed
+        );
+        assert_ok!(Tokens::confirm_redemption(Origin::signed(BOB_ACCOUNT_ID), 0));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::RedemptionConfirmed(0, currency_code, DAVE, 200))
+        );
+        assert_eq!(Tokens::reserved_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 100);
+        assert_eq!(Tokens::total_issuance(currency_code), 800);
+        assert_eq!(Tokens::redemption(0), None);
+
+        assert_ok!(Tokens::reject_redemption(Origin::root(), 1));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::RedemptionRejected(1, currency_code, DAVE, 100))
+        );
+        assert_eq!(Tokens::reserved_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 0);
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 300);
+        assert_noop!(
+            Tokens::reject_redemption(Origin::root(), 1),
+            Error::<Test>::RedemptionNotFound
+        );
+        assert_ok!(Tokens::check_total_issuance());
+
+        RedemptionCount::put(RedemptionIndex::MAX);
+        assert_noop!(
+            Tokens::request_redemption(Origin::signed(DAVE_ACCOUNT_ID), currency_code, 100, b"ref".to_vec().into()),
+            Error::<Test>::RedemptionCountOverflow
+        );
+    });
+}
+
+fn store_kyc_vc(owner: did::Did, owner_account: u64) {
+    let vc_type = vc::VCType::KycVC;
+    let kyc_vc = [0; 128];
+    let pair: sr25519::Pair = sr25519::Pair::from_seed(&ALICE_SEED);
+    let issuers = vec![ALICE];
+    let hash = BlakeTwo256::hash_of(&(&vc_type, &kyc_vc, &owner, &issuers));
+    let signature = pair.sign(hash.as_ref());
+
+    let vc_struct: vc::VC<H256> = vc::VC {
+        hash,
+        signatures: vec![signature],
+        vc_type,
+        owner,
+        issuers,
+        is_vc_used: false,
+        vc_property: kyc_vc,
+    };
+    assert_ok!(VC::store(Origin::signed(owner_account), vc_struct.encode()));
+}
+
+#[test]
+fn test_whitelist_transfer_restriction() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+
+        assert_noop!(
+            Tokens::set_transfer_restriction(Origin::signed(DAVE_ACCOUNT_ID), currency_code, TransferRestriction::Whitelist),
+            Error::<Test>::NotAllowed
+        );
+        assert_ok!(Tokens::set_transfer_restriction(
+            Origin::signed(BOB_ACCOUNT_ID),
+            currency_code,
+            TransferRestriction::Whitelist
+        ));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::TransferRestrictionSet(currency_code, TransferRestriction::Whitelist))
+        );
+        assert_noop!(
+            Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 500),
+            Error::<Test>::NotWhitelisted
+        );
+
+        assert_ok!(Tokens::add_to_whitelist(Origin::signed(BOB_ACCOUNT_ID), currency_code, DAVE));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::AddedToWhitelist(currency_code, DAVE))
+        );
+        assert_ok!(Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 500));
+        // both parties must be whitelisted, the token owner is exempt
+        assert_noop!(
+            Tokens::transfer(Origin::signed(DAVE_ACCOUNT_ID), ALICE_ACCOUNT_ID, currency_code, 100),
+            Error::<Test>::NotWhitelisted
+        );
+        assert_ok!(Tokens::transfer(Origin::signed(DAVE_ACCOUNT_ID), BOB_ACCOUNT_ID, currency_code, 100));
+
+        assert_ok!(Tokens::remove_from_whitelist(Origin::root(), currency_code, DAVE));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::tokens(crate::Event::RemovedFromWhitelist(currency_code, DAVE))
+        );
+        assert_noop!(
+            Tokens::transfer(Origin::signed(DAVE_ACCOUNT_ID), BOB_ACCOUNT_ID, currency_code, 100),
+            Error::<Test>::NotWhitelisted
+        );
+
+        assert_ok!(Tokens::set_transfer_restriction(Origin::root(), currency_code, TransferRestriction::Open));
+        assert_ok!(Tokens::transfer(Origin::signed(DAVE_ACCOUNT_ID), ALICE_ACCOUNT_ID, currency_code, 100));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &ALICE_ACCOUNT_ID), 100);
+    });
+}
+
+#[test]
+fn test_credential_transfer_restriction() {
+    ExtBuilder::default().build().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        issue_test_token(currency_code, 1_000);
+        assert_ok!(Tokens::set_transfer_restriction(
+            Origin::signed(BOB_ACCOUNT_ID),
+            currency_code,
+            TransferRestriction::Credential(ALICE)
+        ));
+
+        assert_noop!(
+            Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 500),
+            Error::<Test>::MissingKycCredential
+        );
+
+        // a KYC VC naming the issuer without its signature is not accepted
+        let vc_type = vc::VCType::KycVC;
+        let kyc_vc = [0; 128];
+        let issuers = vec![DAVE, ALICE];
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &kyc_vc, &DAVE, &issuers));
+        let vc_struct: vc::VC<H256> = vc::VC {
+            hash,
+            signatures: vec![sr25519::Pair::from_seed(&DAVE_SEED).sign(hash.as_ref())],
+            vc_type,
+            owner: DAVE,
+            issuers,
+            is_vc_used: false,
+            vc_property: kyc_vc,
+        };
+        let vc_id = *BlakeTwo256::hash_of(&vc_struct).as_fixed_bytes();
+        assert_ok!(VC::store(Origin::signed(DAVE_ACCOUNT_ID), vc_struct.encode()));
+        assert_ok!(Call::VC(vc::Call::update_status(vc_id, vc::VCStatus::Active))
+            .dispatch(Origin::signed(DAVE_ACCOUNT_ID)));
+        assert_noop!(
+            Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 500),
+            Error::<Test>::MissingKycCredential
+        );
+
+        store_kyc_vc(DAVE, DAVE_ACCOUNT_ID);
+        assert_ok!(Tokens::transfer(Origin::signed(BOB_ACCOUNT_ID), DAVE_ACCOUNT_ID, currency_code, 500));
+        assert_eq!(Tokens::free_balance(TEST_TOKEN_ID, &DAVE_ACCOUNT_ID), 500);
+
+        // the recipient is checked as well
+        assert_noop!(
+            Tokens::transfer(Origin::signed(DAVE_ACCOUNT_ID), ALICE_ACCOUNT_ID, currency_code, 100),
+            Error::<Test>::MissingKycCredential
+        );
+    });
+}
+
+#[test]
+fn test_migrate_from_v2_in_one_upgrade() {
+    sp_io::TestExternalities::default().execute_with(|| {
+        let currency_code = convert_to_array::<8>("OTH".into());
+        let currency_id: CurrencyId = 7;
+        migration::deprecated::TokenData::<Test>::insert(
+            currency_id,
+            V4TokenDetails {
+                token_name: "test".into(),
+                currency_code: "OTH".into(),
+                decimal: 6,
+                block_number: 1,
+            },
+        );
+        migration::deprecated::TokenIssuer::<Test>::insert(currency_id, BOB);
+        migration::deprecated::Accounts::<Test>::insert(
+            BOB,
+            currency_id,
+            TokenAccountInfo {
+                nonce: 0,
+                data: TokenAccountData {
+                    free: 1_000,
+                    ..Default::default()
+                },
+            },
+        );
+        // issuance out of sync with the balances
+        migration::deprecated::TotalIssuance::<Test>::insert(currency_id, 5);
+        PalletVersion::put(StorageVersion::V2_0_0);
+
+        <Tokens as OnRuntimeUpgrade>::on_runtime_upgrade();
+        assert_eq!(PalletVersion::get(), StorageVersion::V5_0_0);
+        assert_eq!(
+            Tokens::token_data(currency_code),
+            Some(TokenDetails {
+                token_name: "test".into(),
+                currency_code: "OTH".into(),
+                decimal: 6,
+                block_number: 1,
+                max_supply: None,
+            })
+        );
+        assert_eq!(Tokens::get_ccy_id_from_ccy_code(&currency_code), currency_id);
+        assert_eq!(Tokens::token_issuer(currency_code), BOB);
+        assert_eq!(Tokens::total_issuance(currency_code), 1_000);
+        assert_eq!(Tokens::mintable_supply(currency_code).unwrap().remaining_supply, None);
+
+        // nothing left to migrate
+        <Tokens as OnRuntimeUpgrade>::on_runtime_upgrade();
+        assert_eq!(PalletVersion::get(), StorageVersion::V5_0_0);
+        assert_eq!(Tokens::total_issuance(currency_code), 1_000);
+    });
+}
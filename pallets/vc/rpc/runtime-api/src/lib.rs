@@ -0,0 +1,14 @@
+//! Runtime API definition for the VC pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+use sp_std::prelude::*;
+use vc::{Did, VCid};
+
+sp_api::decl_runtime_apis! {
+    pub trait VcApi {
+        /// Get the issuers of the given VC who have not signed it yet
+        fn pending_issuers(vc_id: VCid) -> Option<Vec<Did>>;
+    }
+}
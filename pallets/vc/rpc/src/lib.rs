@@ -0,0 +1,70 @@
+//! RPC interface for the VC pallet.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use vc::{Did, VCid};
+pub use vc_rpc_runtime_api::VcApi as VcRuntimeApi;
+
+#[rpc]
+pub trait VcApi<BlockHash> {
+    /// Get the issuers of the given VC who have not signed it yet
+    #[rpc(name = "vc_pendingIssuers")]
+    fn pending_issuers(&self, vc_id: VCid, at: Option<BlockHash>) -> Result<Option<Vec<Did>>>;
+}
+
+/// A struct that implements the `VcApi`.
+pub struct Vc<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Vc<C, B> {
+    /// Create new `Vc` instance with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The call to runtime failed.
+    RuntimeError,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::RuntimeError => 1,
+        }
+    }
+}
+
+impl<C, Block> VcApi<<Block as BlockT>::Hash> for Vc<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: VcRuntimeApi<Block>,
+{
+    fn pending_issuers(
+        &self,
+        vc_id: VCid,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Vec<Did>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.pending_issuers(&at, vc_id).map_err(|e| RpcError {
+            code: ErrorCode::ServerError(Error::RuntimeError.into()),
+            message: "Unable to query pending issuers.".into(),
+            data: Some(format!("{:?}", e).into()),
+        })
+    }
+}
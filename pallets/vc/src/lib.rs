@@ -5,7 +5,8 @@ use frame_support::{
     decl_error, decl_event, decl_module, decl_storage,
     dispatch::DispatchResult,
     ensure, fail,
-    traits::EnsureOrigin,
+    traits::{Currency, EnsureOrigin, ReservableCurrency},
+    transactional,
     weights::Weight,
     StorageMap,
     traits::Get,
@@ -13,7 +14,7 @@ use frame_support::{
 use frame_system::{self, ensure_signed};
 use sp_core::sr25519;
 use sp_runtime::{
-    traits::{BlakeTwo256, Hash, Verify},
+    traits::{BlakeTwo256, Hash, Saturating, Verify, Zero},
     DispatchError,
 };
 use sp_std::{prelude::*, vec};
@@ -25,6 +26,10 @@ mod migration;
 
 pub mod structs;
 pub use crate::structs::*;
+pub mod traits;
+pub use crate::traits::*;
+pub mod origin;
+pub use crate::origin::*;
 
 // describe DID type, not importing from did pallet to avoid circular dependency
 pub type Did = [u8; 32];
@@ -32,10 +37,22 @@ pub type VCid = [u8; 32];
 pub type VCHash = Vec<u8>;
 pub type PublicKey = sr25519::Public;
 
+type BalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 pub trait Config: frame_system::Config + validator_set::Config + did::Config {
     type Event: From<Event> + Into<<Self as frame_system::Config>::Event>;
     /// Origin from which approvals must come.
     type ApproveOrigin: EnsureOrigin<Self::Origin>;
+    /// The currency in which the VC storage deposit is reserved.
+    type Currency: ReservableCurrency<Self::AccountId>;
+    /// The deposit reserved from the submitter of any VC other than TokenVC.
+    type VCDeposit: Get<BalanceOf<Self>>;
+    /// Part of the VC deposit that stays reserved once the VC is used, deactivated or removed,
+    /// paying for its entry in `RemovedVCs`.
+    type VCTombstoneDeposit: Get<BalanceOf<Self>>;
+    /// Number of blocks after which a used or deactivated VC can be removed.
+    type VCMaxAge: Get<Self::BlockNumber>;
 }
 
 decl_storage! {
@@ -50,15 +67,23 @@ decl_storage! {
         pub VCHistory: map hasher(blake2_128_concat) VCid => Option<(VCStatus,T::BlockNumber)>;
         /// map for vc id and approvers list
         pub VCApproverList: map hasher(blake2_128_concat) VCid => Vec<Did>;
+        /// map for vc id and the storage deposit reserved from its submitter, down to
+        /// `VCTombstoneDeposit` once the VC is used, deactivated or removed
+        pub Deposits get(fn deposits): map hasher(blake2_128_concat) VCid => Option<(T::AccountId, BalanceOf<T>)>;
+        /// VCids removed through `remove_vc`, kept so the same VC can't be stored and used again
+        pub RemovedVCs get(fn is_removed): map hasher(blake2_128_concat) VCid => bool;
+        /// VCids set inactive through `update_status`, unlike VCs still waiting for signatures they
+        /// can be removed
+        pub DeactivatedVCs get(fn is_deactivated): map hasher(blake2_128_concat) VCid => bool;
         /// The current version of the pallet
         PalletVersion: VCPalletVersion = VCPalletVersion::V1_0_0
     }
-    // add_extra_genesis {
-    //     config(init_vcs): Vec<InitialVCs>;
-    //     build(|config: &GenesisConfig | {
-    //         <Module<T>>::initialize_vcs(&config.init_vcs)
-    //     })
-    // }
+    add_extra_genesis {
+        config(init_vcs): Vec<VCHash>;
+        build(|config: &GenesisConfig| {
+            <Module<T>>::initialize_vcs(&config.init_vcs)
+        })
+    }
 }
 
 decl_event!(
@@ -67,6 +92,10 @@ decl_event!(
         VCValidated(VCid),
         /// Updated VC status flag
         VCStatusUpdated(VCid, VCStatus),
+        /// All issuers of the VC have signed it
+        VCFullyApproved(VCid),
+        /// VC has been removed from storage
+        VCRemoved(VCid),
     }
 );
 
@@ -95,7 +124,17 @@ decl_error! {
         /// Either signature is invalid or signer is not a valid issuer 
         InvalidSignature,
         /// The issuer has already approved the VC
-        DuplicateSignature
+        DuplicateSignature,
+        /// No signature was provided
+        NoSignaturesProvided,
+        /// TokenVC backs an issued token and can't be removed
+        TokenVCNotRemovable,
+        /// Only used or deactivated VCs can be removed
+        VCStillInUse,
+        /// VC is not old enough to be removed
+        VCNotExpired,
+        /// VC was removed and can't be stored again
+        VCWasRemoved
     }
 }
 
@@ -106,28 +145,44 @@ decl_module! {
         type Error = Error<T>;
 
         /// Adds a member to the membership set
+        ///
+        /// Any VC other than TokenVC reserves `VCDeposit` from the submitter until it is used,
+        /// marked inactive or removed, `VCTombstoneDeposit` of it stays reserved afterwards
         #[weight = 1]
+        #[transactional]
         pub fn store(origin, vc_hex: VCHash) -> DispatchResult {
             // Extracting vc from encoded vc byte array
             let vc: VC<T::Hash> = Self::get_vc(&vc_hex)?;
 
-            match vc.vc_type {
+            let depositor = match vc.vc_type {
                 VCType::TokenVC => {
                     // Check if the origin of the call is approved orgin or not
                     <T as Config>::ApproveOrigin::ensure_origin(origin)?;
                     // Check if owner's did is registered or not
                     let _ = did::Module::<T>::get_accountid_from_did(&vc.owner)?;
+                    None
                 }
                 VCType::SlashTokens | VCType::MintTokens | VCType::TokenTransferVC => {
+                    let sender = ensure_signed(origin)?;
                     // Validating owner of slash or token vc is one of the issuers or not
                     Self::validate_vcs(&vc)?;
+                    Some(sender)
                 }
-            }
+                VCType::KycVC => {
+                    let sender = ensure_signed(origin)?;
+                    // Check if owner's did is registered or not
+                    let _ = did::Module::<T>::get_accountid_from_did(&vc.owner)?;
+                    Some(sender)
+                }
+            };
 
             // Generating vc_id from vc to emit in the event
             let vc_id: VCid = *BlakeTwo256::hash_of(&vc).as_fixed_bytes();
             // storing hash
             Self::store_vc(vc.owner, vc, vc_id)?;
+            if let Some(depositor) = depositor {
+                Self::reserve_deposit(depositor, vc_id)?;
+            }
             Self::deposit_event(Event::VCValidated(vc_id));
             Ok(())
         }
@@ -156,7 +211,39 @@ decl_module! {
             Ok(())
         }
 
+        /// Update multiple signatures of vc_hash in a single call
+        ///
+        /// Every signature must belong to a distinct issuer that has not approved the VC yet,
+        /// otherwise none of them are stored
+        #[weight = 1]
+        #[transactional]
+        fn add_signatures(origin, vc_id: VCid, signs: Vec<Signature>) -> DispatchResult {
+            // Ensure caller is signed account
+            let senders_acccount_id = ensure_signed(origin)?;
+
+            Self::validate_updater(&senders_acccount_id, &vc_id)?;
+
+            ensure!(!signs.is_empty(), Error::<T>::NoSignaturesProvided);
+
+            let (mut vc, _) = if let Some(vcs_details) = VCs::<T>::get(vc_id) {
+                (vcs_details.0, vcs_details.1)
+            } else {
+                fail!(Error::<T>::VCIdDoesNotExist)
+            };
+
+            for sign in signs.into_iter() {
+                Self::validate_sign(&vc, sign.clone(), vc_id)?;
+                vc.signatures.push(sign);
+            }
+
+            Self::update_vc_and_status(vc_id, vc)?;
+            Ok(())
+        }
+
         /// Update status of vc_hash wheather it is active or inactive
+        ///
+        /// Setting a VC inactive returns its deposit, the VC can still be activated again until it
+        /// is removed
         #[weight = 1]
         fn update_status(origin, vc_id: VCid, vc_status: VCStatus) -> DispatchResult {
             // Ensure caller is signed account
@@ -166,6 +253,56 @@ decl_module! {
 
             Self::update_vc_status(vc_id, vc_status)?;
 
+            if vc_status == VCStatus::Inactive {
+                Self::release_deposit(vc_id);
+                DeactivatedVCs::insert(vc_id, true);
+            } else {
+                DeactivatedVCs::remove(vc_id);
+            }
+
+            Ok(())
+        }
+
+        /// Remove a used or deactivated VC that is older than `VCMaxAge`
+        ///
+        /// VCs that are inactive because they still miss signatures can't be removed.
+        ///
+        /// Can be called by any signed account, the storage deposit is returned to the submitter
+        #[weight = 1]
+        fn remove_vc(origin, vc_id: VCid) -> DispatchResult {
+            let _ = ensure_signed(origin)?;
+
+            let (vc, vc_status) = if let Some(vcs_details) = VCs::<T>::get(vc_id) {
+                (vcs_details.0, vcs_details.1)
+            } else {
+                fail!(Error::<T>::VCIdDoesNotExist)
+            };
+
+            ensure!(vc.vc_type != VCType::TokenVC, Error::<T>::TokenVCNotRemovable);
+            ensure!(
+                vc.is_vc_used || (DeactivatedVCs::get(vc_id) && vc_status == VCStatus::Inactive),
+                Error::<T>::VCStillInUse
+            );
+
+            let current_block_no = <frame_system::Module<T>>::block_number();
+            if let Some((_, created_block_no)) = VCHistory::<T>::get(vc_id) {
+                ensure!(
+                    current_block_no >= created_block_no.saturating_add(T::VCMaxAge::get()),
+                    Error::<T>::VCNotExpired
+                );
+            }
+
+            Self::release_deposit(vc_id);
+
+            VCs::<T>::remove(vc_id);
+            VCHistory::<T>::remove(vc_id);
+            VCApproverList::remove(vc_id);
+            DeactivatedVCs::remove(vc_id);
+            let owner = RLookup::take(vc_id);
+            Lookup::mutate(owner, |vc_ids| vc_ids.retain(|id| *id != vc_id));
+            RemovedVCs::insert(vc_id, true);
+
+            Self::deposit_event(Event::VCRemoved(vc_id));
             Ok(())
         }
 
@@ -245,23 +382,43 @@ impl<T: Config> Module<T> {
         Ok(())
     }
 
-    // // load initial list of validators from genesis
-    // fn initialize_vcs(init_vcs: &Vec<InitialVCs>) {
-    //     for init_vc in init_vcs.iter() {
-    //         let block_no: T::BlockNumber = 0u32.into();
+    /// Check that the VC is usable, of the given type and signed by the given issuer
+    pub(crate) fn is_active_vc_from(vc_id: &VCid, vc_type: &VCType, issuer: &Did) -> bool {
+        Self::get_usable_vc(vc_id)
+            .map(|vc| vc.vc_type == *vc_type && vc.issuers.contains(issuer))
+            .unwrap_or(false)
+            // a co-issuer or validator can activate the VC without the issuer's signature
+            && VCApproverList::get(vc_id).contains(issuer)
+    }
 
-    //         VCs::<T>::insert(init_vc.identifier.clone(), (init_vc.vcs.clone(), block_no));
+    /// Load initial list of fully signed VCs from genesis
+    ///
+    /// Panics if any of the VCs can't be decoded, is not signed by all of its issuers
+    /// or links to a VC that is not part of the genesis
+    fn initialize_vcs(init_vcs: &[VCHash]) {
+        for vc_hex in init_vcs.iter() {
+            let vc: VC<T::Hash> = Self::get_vc(vc_hex).expect("Genesis VC must be decodable");
 
-    //         let account_id = did::Module::<T>::get_accountid_from_pubkey(&init_vc.public_key);
-    //         for vc in init_vc.vcs.iter() {
-    //             Lookup::<T>::insert(vc, account_id.clone());
-    //         }
+            match vc.vc_type {
+                VCType::TokenVC | VCType::KycVC => {
+                    did::Module::<T>::get_accountid_from_did(&vc.owner)
+                        .expect("Owner of genesis VC must be a registered DID");
+                }
+                VCType::SlashTokens | VCType::MintTokens | VCType::TokenTransferVC => {
+                    Self::validate_vcs(&vc).expect("Genesis VC must be signed by TokenVC owner");
+                }
+            }
 
-    //         RLookup::<T>::insert(account_id.clone(), init_vc.vcs.clone());
-    //         Members::put(init_vc.vcs.clone());
-    //         DIDs::<T>::insert(account_id, init_vc.identifier);
-    //     }
-    // }
+            assert_eq!(
+                Self::get_vc_status(&vc).expect("Genesis VC must be valid"),
+                VCStatus::Active,
+                "Genesis VC must be signed by all of its issuers"
+            );
+
+            let vc_id: VCid = *BlakeTwo256::hash_of(&vc).as_fixed_bytes();
+            Self::store_vc(vc.owner, vc, vc_id).expect("Genesis VC must be unique");
+        }
+    }
 
     /// Validating VC
     pub fn get_vc_status(vc: &VC<T::Hash>) -> Result<VCStatus, DispatchError> {
@@ -315,6 +472,7 @@ impl<T: Config> Module<T> {
 
         // Check if vc already exists
         ensure!(!RLookup::contains_key(&vc_id), Error::<T>::VCAlreadyExists);
+        ensure!(!RemovedVCs::get(vc_id), Error::<T>::VCWasRemoved);
         
         Self::set_approved_issuers(vc_id, &vc)?;
 
@@ -331,6 +489,10 @@ impl<T: Config> Module<T> {
 
         VCHistory::<T>::insert(vc_id, (vc_status, current_block_no));
 
+        if vc_status == VCStatus::Active {
+            Self::deposit_event(Event::VCFullyApproved(vc_id));
+        }
+
         Ok(())
     }
 
@@ -352,6 +514,7 @@ impl<T: Config> Module<T> {
     // Update VC and vc_status from storage
     fn update_vc_and_status(vc_id: VCid, updated_vc: VC<T::Hash>) -> Result<(), DispatchError> {
         let status = Self::get_vc_status(&updated_vc)?;
+        let prev_status = VCs::<T>::get(vc_id).map(|(_, vc_status)| vc_status);
         VCs::<T>::insert(vc_id, (updated_vc, status));
 
         if let Some(vc_history) = VCHistory::<T>::get(&vc_id) {
@@ -359,17 +522,60 @@ impl<T: Config> Module<T> {
         }
 
         Self::deposit_event(Event::VCStatusUpdated(vc_id, status));
+        if status == VCStatus::Active && prev_status != Some(VCStatus::Active) {
+            Self::deposit_event(Event::VCFullyApproved(vc_id));
+        }
         Ok(())
     }
 
+    /// Get the issuers of the given VC whose signature is still pending
+    ///
+    /// Returns `None` if the VC does not exist
+    pub fn get_pending_issuers(vc_id: VCid) -> Option<Vec<Did>> {
+        let (vc, _) = VCs::<T>::get(vc_id)?;
+        let approvers = VCApproverList::get(vc_id);
+        Some(
+            vc.issuers
+                .into_iter()
+                .filter(|issuer| !approvers.contains(issuer))
+                .collect(),
+        )
+    }
+
     /// Update vc's is_used flag to true
     pub fn set_is_used_flag(vc_id: VCid) {
         if let Some((mut vc, status)) = VCs::<T>::get(&vc_id) {
             vc.is_vc_used = true;
             VCs::<T>::insert(vc_id, (vc, status));
+            Self::release_deposit(vc_id);
         }
     }
 
+    /// Reserve the VC storage deposit from the submitter
+    fn reserve_deposit(depositor: T::AccountId, vc_id: VCid) -> DispatchResult {
+        let deposit = T::VCDeposit::get();
+        T::Currency::reserve(&depositor, deposit)?;
+        Deposits::<T>::insert(vc_id, (depositor, deposit));
+        Ok(())
+    }
+
+    /// Return the VC storage deposit to the submitter, if any
+    ///
+    /// `VCTombstoneDeposit` stays reserved, it pays for the tombstone kept once the VC is removed.
+    fn release_deposit(vc_id: VCid) {
+        Deposits::<T>::mutate_exists(vc_id, |maybe_deposit| {
+            if let Some((depositor, deposit)) = maybe_deposit {
+                let kept = (*deposit).min(T::VCTombstoneDeposit::get());
+                T::Currency::unreserve(depositor, deposit.saturating_sub(kept));
+                if kept.is_zero() {
+                    *maybe_deposit = None;
+                } else {
+                    *deposit = kept;
+                }
+            }
+        });
+    }
+
     // Validate sign
     fn validate_sign(vc: &VC<T::Hash>, sign: Signature, vc_id: VCid) -> Result<(), DispatchError> {
         let mut is_sign_valid = false;
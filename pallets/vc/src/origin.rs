@@ -0,0 +1,59 @@
+use super::*;
+use frame_system::RawOrigin;
+use sp_std::marker::PhantomData;
+
+/// Origin check that only passes for signed senders holding a credential
+///
+/// The sender's DID is resolved through the did pallet and must hold an active, unused VC of
+/// type `VcType` issued and signed by one of the DIDs returned by `Issuers`. VCs carry no expiry of
+/// their own, so a credential expires `MaxAge` blocks after it was stored. An issuer can expire it
+/// earlier by setting its status to `Inactive`.
+pub struct EnsureHasCredential<T, VcType, Issuers, MaxAge>(
+    PhantomData<(T, VcType, Issuers, MaxAge)>,
+);
+
+impl<T, VcType, Issuers, MaxAge> EnsureOrigin<T::Origin>
+    for EnsureHasCredential<T, VcType, Issuers, MaxAge>
+where
+    T: Config,
+    VcType: Get<VCType>,
+    Issuers: Get<Vec<Did>>,
+    MaxAge: Get<T::BlockNumber>,
+{
+    type Success = T::AccountId;
+
+    fn try_origin(o: T::Origin) -> Result<Self::Success, T::Origin> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Signed(who) => {
+                if did::Module::<T>::does_did_exist(&who) {
+                    let did = did::Module::<T>::get_did_from_account_id(&who);
+                    let vc_type = VcType::get();
+                    let issuers = Issuers::get();
+                    let now = <frame_system::Module<T>>::block_number();
+                    let has_credential = Lookup::get(did).iter().any(|vc_id| {
+                        issuers
+                            .iter()
+                            .any(|issuer| Module::<T>::is_active_vc_from(vc_id, &vc_type, issuer))
+                            && matches!(
+                                VCHistory::<T>::get(vc_id),
+                                Some((_, created_block_no))
+                                    if now < created_block_no.saturating_add(MaxAge::get())
+                            )
+                    });
+                    if has_credential {
+                        return Ok(who);
+                    }
+                }
+                Err(T::Origin::from(RawOrigin::Signed(who)))
+            }
+            r => Err(T::Origin::from(r)),
+        })
+    }
+
+    /// Benchmarks using this origin must register a DID for the default account and store a
+    /// matching credential for it
+    #[cfg(feature = "runtime-benchmarks")]
+    fn successful_origin() -> T::Origin {
+        T::Origin::from(RawOrigin::Signed(Default::default()))
+    }
+}
@@ -18,6 +18,8 @@ pub enum VCType {
     SlashTokens,
     MintTokens,
     TokenTransferVC,
+    /// Attests the owner passed KYC checks of the issuers
+    KycVC,
 }
 
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default)]
@@ -27,6 +29,8 @@ pub struct TokenVC {
     pub reservable_balance: u128,
     pub decimal: u8,
     pub currency_code: [u8; 8],
+    /// Hard cap on the total issuance of the token, unlimited when `None`
+    pub max_supply: Option<u128>,
 }
 
 #[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, Default)]
@@ -52,14 +56,6 @@ pub enum VCStatus {
     Inactive,
 }
 
-#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct InitialVCs {
-    pub identifier: Did,
-    pub public_key: PublicKey,
-    pub vcs: Vec<VCHash>,
-}
-
 pub trait HasVCId {
     fn vc_id(&self) -> VCid;
 }
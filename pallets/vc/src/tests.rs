@@ -1,7 +1,9 @@
 use super::*;
 use crate::{self as verified_credentials, Config};
 use did;
-use frame_support::{assert_noop, assert_ok, ord_parameter_types, parameter_types};
+use frame_support::{
+    assert_noop, assert_ok, ord_parameter_types, parameter_types, traits::StorageMapShim,
+};
 use frame_system::EnsureSignedBy;
 use sp_core::{sr25519, Pair, H256};
 use sp_runtime::{
@@ -29,6 +31,7 @@ frame_support::construct_runtime!(
         VC: verified_credentials::{Module, Call, Storage, Event},
         ValidatorSet: validator_set::{Module, Call, Storage, Event, Config},
         Did: did::{Module, Call, Storage, Event, Config},
+        Balances: balances::{Module, Call, Storage, Event<T>, Config<T>},
     }
 );
 
@@ -55,18 +58,58 @@ impl frame_system::Config for Test {
     type DbWeight = ();
     type Version = ();
     type PalletInfo = PalletInfo;
-    type AccountData = ();
+    type AccountData = balances::AccountData<u64>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
     type SS58Prefix = SS58Prefix;
 }
 
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = StorageMapShim<
+        balances::Account<Test>,
+        frame_system::Provider<Test>,
+        u64,
+        balances::AccountData<u64>,
+    >;
+    type MaxLocks = MaxLocks;
+    type WeightInfo = ();
+    type DidResolution = did::Module<Test>;
+}
+
+parameter_types! {
+    pub const VCDeposit: u64 = VC_DEPOSIT;
+    pub const VCTombstoneDeposit: u64 = VC_TOMBSTONE_DEPOSIT;
+    pub const VCMaxAge: u64 = VC_MAX_AGE;
+}
+
 impl Config for Test {
     type Event = Event;
     type ApproveOrigin = EnsureSignedBy<ValidAccount, u64>;
+    type Currency = Balances;
+    type VCDeposit = VCDeposit;
+    type VCTombstoneDeposit = VCTombstoneDeposit;
+    type VCMaxAge = VCMaxAge;
 }
 
+parameter_types! {
+    pub const CredentialType: VCType = VCType::TokenVC;
+    pub AcceptedIssuers: Vec<did::Did> = vec![BOB];
+    pub const CredentialMaxAge: u64 = CREDENTIAL_MAX_AGE;
+}
+
+type EnsureBobCredential =
+    EnsureHasCredential<Test, CredentialType, AcceptedIssuers, CredentialMaxAge>;
+
 impl validator_set::Config for Test {
     type Event = Event;
     type ApproveOrigin = EnsureSignedBy<ValidAccount, u64>;
@@ -77,12 +120,18 @@ impl did::Config for Test {
 }
 
 const NON_VALIDATOR_ACCOUNT: u64 = 2;
+const VC_DEPOSIT: u64 = 100;
+const VC_TOMBSTONE_DEPOSIT: u64 = 10;
+const VC_MAX_AGE: u64 = 10;
+const CREDENTIAL_MAX_AGE: u64 = 5;
+const INITIAL_BALANCE: u64 = 1_000;
 const ALICE: did::Did = *b"did:ssid:swn\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
 const BOB: did::Did = *b"did:ssid:bob\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
 const DAVE: did::Did = *b"did:ssid:dave\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
 const EVE: did::Did = *b"did:ssid:eve\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
 const ALICE_ACCOUNT_ID: u64 = 2077282123132384724;
 const BOB_ACCOUNT_ID: u64 = 7166219960988249998;
+const DAVE_ACCOUNT_ID: u64 = 13620103657161844528;
 const ALICE_SEED: [u8; 32] = [
     229, 190, 154, 80, 146, 184, 27, 202, 100, 190, 129, 210, 18, 231, 242, 249, 235, 161, 131,
     187, 122, 144, 149, 79, 123, 118, 54, 31, 110, 219, 92, 10,
@@ -108,27 +157,6 @@ fn new_test_ext() -> sp_io::TestExternalities {
         .unwrap();
 
     let pair: sr25519::Pair = sr25519::Pair::from_seed(&ALICE_SEED);
-    // let vc: Vec<u8> = vec![
-    //     65, 108, 105, 99, 101, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    //     0, 0, 0, 0, 65, 108, 105, 99, 101, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    //     0, 0, 0, 0, 0, 0, 0, 0, 252, 48, 108, 23, 89, 14, 11, 176, 20, 20, 127, 49, 19, 39, 250,
-    //     139, 21, 93, 151, 92, 223, 184, 79, 121, 58, 205, 209, 146, 217, 162, 229, 89, 0, 188, 126,
-    //     39, 174, 121, 218, 194, 177, 241, 52, 236, 12, 23, 109, 17, 189, 18, 217, 32, 212, 161, 19,
-    //     146, 184, 151, 105, 127, 117, 166, 226, 194, 8, 32, 11, 234, 171, 157, 238, 181, 242, 28,
-    //     186, 194, 248, 72, 187, 234, 120, 167, 193, 252, 153, 117, 74, 171, 197, 5, 51, 34, 153,
-    //     238, 130, 187, 73, 62, 218, 54, 238, 25, 197, 216, 225, 168, 235, 123, 41, 83, 37, 67, 253,
-    //     185, 1, 168, 184, 49, 226, 45, 108, 219, 108, 88, 130, 255, 152, 119, 130, 1,
-    // ];
-
-    // super::GenesisConfig {
-    //     init_vcs: vec![InitialVCs {
-    //         identifier: ALICE,
-    //         public_key: pair.public(),
-    //         vcs: vec![vc],
-    //     }],
-    // }
-    // .assimilate_storage::<Test>(&mut o)
-    // .unwrap();
 
     validator_set::GenesisConfig {
         validators: vec![ALICE],
@@ -146,32 +174,136 @@ fn new_test_ext() -> sp_io::TestExternalities {
     .assimilate_storage::<Test>(&mut o)
     .unwrap();
 
+    balances::GenesisConfig::<Test> {
+        balances: vec![
+            (ALICE_ACCOUNT_ID, INITIAL_BALANCE),
+            (BOB_ACCOUNT_ID, INITIAL_BALANCE),
+            (DAVE_ACCOUNT_ID, INITIAL_BALANCE),
+        ],
+    }
+    .assimilate_storage(&mut o)
+    .unwrap();
+
     o.into()
 }
 
-// #[test]
-// fn test_genesis_worked() {
-//     new_test_ext().execute_with(|| {
-//         assert!(VCs::<Test>::contains_key(ALICE));
-//         assert!(DIDs::<Test>::contains_key(ALICE_ACCOUNT_ID));
-//         assert!(RLookup::<Test>::contains_key(ALICE_ACCOUNT_ID));
-
-//         let genesis_vc: Vec<u8> = vec![
-//             65, 108, 105, 99, 101, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-//             0, 0, 0, 0, 0, 0, 65, 108, 105, 99, 101, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-//             0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 252, 48, 108, 23, 89, 14, 11, 176, 20, 20, 127, 49,
-//             19, 39, 250, 139, 21, 93, 151, 92, 223, 184, 79, 121, 58, 205, 209, 146, 217, 162, 229,
-//             89, 0, 188, 126, 39, 174, 121, 218, 194, 177, 241, 52, 236, 12, 23, 109, 17, 189, 18,
-//             217, 32, 212, 161, 19, 146, 184, 151, 105, 127, 117, 166, 226, 194, 8, 32, 11, 234,
-//             171, 157, 238, 181, 242, 28, 186, 194, 248, 72, 187, 234, 120, 167, 193, 252, 153, 117,
-//             74, 171, 197, 5, 51, 34, 153, 238, 130, 187, 73, 62, 218, 54, 238, 25, 197, 216, 225,
-//             168, 235, 123, 41, 83, 37, 67, 253, 185, 1, 168, 184, 49, 226, 45, 108, 219, 108, 88,
-//             130, 255, 152, 119, 130, 1,
-//         ];
-//         assert!(Members::get().contains(&genesis_vc));
-//         assert!(Lookup::<Test>::contains_key(genesis_vc));
-//     })
-// }
+#[test]
+fn test_genesis_worked() {
+    let pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+
+    let token_vc = TokenVC {
+        token_name: convert_to_array::<16>("test".into()),
+        reservable_balance: 1000,
+        decimal: 6,
+        currency_code: convert_to_array::<8>("OTH".into()),
+        max_supply: None,
+    };
+
+    let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+    let vc_type = VCType::TokenVC;
+    let owner = BOB;
+    let issuers = vec![BOB];
+    let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+    let signature = pair.sign(hash.as_ref());
+
+    let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+        hash,
+        signatures: vec![signature],
+        vc_type,
+        owner,
+        issuers,
+        is_vc_used: false,
+        vc_property: token_vc,
+    };
+
+    let mut o = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    did::GenesisConfig {
+        dids: vec![did::DidStruct {
+            identifier: BOB,
+            public_key: pair.public(),
+            metadata: Vec::default(),
+        }],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    super::GenesisConfig {
+        init_vcs: vec![vc.encode()],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    sp_io::TestExternalities::from(o).execute_with(|| {
+        let vc_id = *BlakeTwo256::hash_of(&vc).as_fixed_bytes();
+        assert_eq!(Lookup::get(BOB), vec![vc_id]);
+        assert_eq!(RLookup::get(vc_id), BOB);
+        assert_eq!(VCs::<Test>::get(vc_id), Some((vc, VCStatus::Active)));
+        assert_eq!(VCApproverList::get(vc_id), vec![BOB]);
+        assert_eq!(VCHistory::<Test>::get(vc_id), Some((VCStatus::Active, 0)));
+    })
+}
+
+#[test]
+#[should_panic(expected = "Genesis VC must be signed by all of its issuers")]
+fn test_genesis_rejects_partially_signed_vc() {
+    let pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+    let dave_pair: sr25519::Pair = sr25519::Pair::from_seed(&DAVE_SEED);
+
+    let token_vc = TokenVC {
+        token_name: convert_to_array::<16>("test".into()),
+        reservable_balance: 1000,
+        decimal: 6,
+        currency_code: convert_to_array::<8>("OTH".into()),
+        max_supply: None,
+    };
+
+    let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+    let vc_type = VCType::TokenVC;
+    let owner = BOB;
+    let issuers = vec![BOB, DAVE];
+    let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+    let signature = pair.sign(hash.as_ref());
+
+    let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+        hash,
+        signatures: vec![signature],
+        vc_type,
+        owner,
+        issuers,
+        is_vc_used: false,
+        vc_property: token_vc,
+    };
+
+    let mut o = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    did::GenesisConfig {
+        dids: vec![
+            did::DidStruct {
+                identifier: BOB,
+                public_key: pair.public(),
+                metadata: Vec::default(),
+            },
+            did::DidStruct {
+                identifier: DAVE,
+                public_key: dave_pair.public(),
+                metadata: Vec::default(),
+            },
+        ],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    super::GenesisConfig {
+        init_vcs: vec![vc.encode()],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+}
 
 fn convert_to_array<const N: usize>(mut v: Vec<u8>) -> [u8; N] {
     if v.len() != N {
@@ -195,6 +327,7 @@ fn test_store() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -254,6 +387,7 @@ fn test_update_status() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -275,7 +409,7 @@ fn test_update_status() {
 
         assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
 
-        let vc_id = Lookup::get(&BOB)[0];
+        let vc_id = Lookup::get(BOB)[0];
         // Updating status flag
         assert_ok!(VC::update_status(
             Origin::signed(ALICE_ACCOUNT_ID),
@@ -306,6 +440,7 @@ fn test_store_vc_with_different_account() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -351,6 +486,7 @@ fn test_store_vc_with_wrong_hash() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -395,6 +531,7 @@ fn test_store_vc_with_wrong_signature() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -441,6 +578,7 @@ fn test_store_vc_less_approvers() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -487,6 +625,7 @@ fn test_update_status_sender() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -508,7 +647,7 @@ fn test_update_status_sender() {
 
         assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
 
-        let vc_id = Lookup::get(&BOB)[0];
+        let vc_id = Lookup::get(BOB)[0];
         let non_issuer = 0;
 
         // Updating status flag with non issuer account
@@ -546,6 +685,7 @@ fn test_add_signature() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -593,7 +733,7 @@ fn test_add_signature() {
 
         assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
 
-        let vc_id = Lookup::get(&BOB)[0];
+        let vc_id = Lookup::get(BOB)[0];
 
         // vc_status = Inactive as only one issuer signed
         assert_eq!(
@@ -666,6 +806,7 @@ fn test_add_signature_with_one_of_the_signers() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -715,7 +856,7 @@ fn test_add_signature_with_one_of_the_signers() {
 
         assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
 
-        let vc_id = Lookup::get(&BOB)[0];
+        let vc_id = Lookup::get(BOB)[0];
 
         // vc_status = Inactive as only one issuer signed
         assert_eq!(
@@ -790,6 +931,7 @@ fn test_set_is_used_flag() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
@@ -811,7 +953,7 @@ fn test_set_is_used_flag() {
 
         assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
 
-        let vc_id = Lookup::get(&BOB)[0];
+        let vc_id = Lookup::get(BOB)[0];
 
         // set vc is_used flag as true
         VC::set_is_used_flag(vc_id);
@@ -831,6 +973,7 @@ fn test_duplicate_issuers_signatures() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         // case when duplicate signatures are present
@@ -903,6 +1046,7 @@ fn test_add_duplicate_issuer_signatures() {
             reservable_balance: 1000,
             decimal: 6,
             currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
         };
 
         // case when duplicate signatures are present
@@ -966,7 +1110,7 @@ fn test_add_duplicate_issuer_signatures() {
 
         assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
         
-        let vc_id = Lookup::get(&BOB)[0];
+        let vc_id = Lookup::get(BOB)[0];
 
         assert_noop!(
             VC::add_signature(
@@ -978,3 +1122,591 @@ fn test_add_duplicate_issuer_signatures() {
         );
     })
 }
+
+#[test]
+fn test_add_signatures() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        let bob_pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+        let dave_pair: sr25519::Pair = sr25519::Pair::from_seed(&DAVE_SEED);
+        let eve_pair: sr25519::Pair = sr25519::Pair::from_seed(&EVE_SEED);
+
+        let token_vc = TokenVC {
+            token_name: convert_to_array::<16>("test".into()),
+            reservable_balance: 1000,
+            decimal: 6,
+            currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
+        };
+
+        let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+        let vc_type = VCType::TokenVC;
+        let owner = BOB;
+        let issuers = vec![BOB, DAVE, EVE];
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+        let bob_sign = bob_pair.sign(hash.as_ref());
+        let dave_sign = dave_pair.sign(hash.as_ref());
+        let eve_sign = eve_pair.sign(hash.as_ref());
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_sign.clone()],
+            vc_type: vc_type.clone(),
+            owner,
+            issuers: issuers.clone(),
+            is_vc_used: true,
+            vc_property: token_vc,
+        };
+
+        // creating BOB's, DAVE's and EVE's did
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            bob_pair.public(),
+            BOB,
+            Vec::new()
+        ));
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            dave_pair.public(),
+            DAVE,
+            Vec::new()
+        ));
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            eve_pair.public(),
+            EVE,
+            Vec::new()
+        ));
+
+        assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
+
+        let vc_id = Lookup::get(BOB)[0];
+
+        // DAVE and EVE are yet to sign
+        assert_eq!(VC::get_pending_issuers(vc_id), Some(vec![DAVE, EVE]));
+
+        // empty list of signatures is rejected
+        assert_noop!(
+            VC::add_signatures(Origin::signed(ALICE_ACCOUNT_ID), vc_id, vec![]),
+            Error::<Test>::NoSignaturesProvided
+        );
+
+        // one duplicate signature rejects the whole batch
+        assert_noop!(
+            VC::add_signatures(
+                Origin::signed(ALICE_ACCOUNT_ID),
+                vc_id,
+                vec![dave_sign.clone(), bob_sign.clone()]
+            ),
+            Error::<Test>::DuplicateSignature
+        );
+        assert_eq!(VCApproverList::get(vc_id), vec![BOB]);
+
+        assert_ok!(VC::add_signatures(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            vc_id,
+            vec![dave_sign.clone(), eve_sign.clone()]
+        ));
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_sign, dave_sign, eve_sign],
+            vc_type,
+            owner,
+            issuers,
+            is_vc_used: true,
+            vc_property: token_vc,
+        };
+
+        // vc_status = Active as all issuers signed
+        assert_eq!(VCs::<Test>::get(vc_id), Some((vc, VCStatus::Active)));
+        assert_eq!(VC::get_pending_issuers(vc_id), Some(vec![]));
+        assert!(System::events().iter().any(|record| record.event
+            == Event::verified_credentials(crate::Event::VCFullyApproved(vc_id))));
+    })
+}
+
+#[test]
+fn test_pending_issuers_of_unknown_vc() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(VC::get_pending_issuers([0; 32]), None);
+    })
+}
+
+#[test]
+fn test_vc_deposit_and_removal() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(1);
+
+        let bob_pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            bob_pair.public(),
+            BOB,
+            Vec::new()
+        ));
+
+        let currency_code = convert_to_array::<8>("OTH".into());
+        let token_vc = TokenVC {
+            token_name: convert_to_array::<16>("test".into()),
+            reservable_balance: 1000,
+            decimal: 6,
+            currency_code,
+            max_supply: None,
+        };
+
+        let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+        let vc_type = VCType::TokenVC;
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &BOB, &vec![BOB]));
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_pair.sign(hash.as_ref())],
+            vc_type,
+            owner: BOB,
+            issuers: vec![BOB],
+            is_vc_used: false,
+            vc_property: token_vc,
+        };
+
+        // TokenVC is stored through the approve origin without any deposit
+        assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
+        let token_vc_id = Lookup::get(BOB)[0];
+        assert_eq!(VC::deposits(token_vc_id), None);
+        assert_eq!(Balances::reserved_balance(BOB_ACCOUNT_ID), 0);
+
+        let mint_vc = SlashMintTokens {
+            vc_id: token_vc_id,
+            currency_code,
+            amount: 1_000,
+        };
+
+        let mint_vc: [u8; 128] = convert_to_array::<128>(mint_vc.encode());
+        let vc_type = VCType::MintTokens;
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &mint_vc, &DAVE, &vec![BOB]));
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_pair.sign(hash.as_ref())],
+            vc_type,
+            owner: DAVE,
+            issuers: vec![BOB],
+            is_vc_used: false,
+            vc_property: mint_vc,
+        };
+
+        // the submitter of the mint vc pays the deposit
+        assert_ok!(VC::store(Origin::signed(DAVE_ACCOUNT_ID), vc.encode()));
+        let vc_id = Lookup::get(DAVE)[0];
+        assert_eq!(VC::deposits(vc_id), Some((DAVE_ACCOUNT_ID, VC_DEPOSIT)));
+        assert_eq!(Balances::reserved_balance(DAVE_ACCOUNT_ID), VC_DEPOSIT);
+        assert_eq!(
+            Balances::free_balance(DAVE_ACCOUNT_ID),
+            INITIAL_BALANCE - VC_DEPOSIT
+        );
+
+        // TokenVC can never be removed
+        assert_noop!(
+            VC::remove_vc(Origin::signed(ALICE_ACCOUNT_ID), token_vc_id),
+            Error::<Test>::TokenVCNotRemovable
+        );
+
+        // active and unused vc can't be removed
+        assert_noop!(
+            VC::remove_vc(Origin::signed(ALICE_ACCOUNT_ID), vc_id),
+            Error::<Test>::VCStillInUse
+        );
+
+        // using the vc returns the deposit but the tombstone part
+        VC::set_is_used_flag(vc_id);
+        assert_eq!(VC::deposits(vc_id), Some((DAVE_ACCOUNT_ID, VC_TOMBSTONE_DEPOSIT)));
+        assert_eq!(Balances::reserved_balance(DAVE_ACCOUNT_ID), VC_TOMBSTONE_DEPOSIT);
+        assert_eq!(
+            Balances::free_balance(DAVE_ACCOUNT_ID),
+            INITIAL_BALANCE - VC_TOMBSTONE_DEPOSIT
+        );
+
+        assert_noop!(
+            VC::remove_vc(Origin::signed(ALICE_ACCOUNT_ID), vc_id),
+            Error::<Test>::VCNotExpired
+        );
+
+        System::set_block_number(1 + VC_MAX_AGE);
+
+        assert_ok!(VC::remove_vc(Origin::signed(ALICE_ACCOUNT_ID), vc_id));
+        assert_eq!(VCs::<Test>::get(vc_id), None);
+        assert_eq!(VCHistory::<Test>::get(vc_id), None);
+        assert!(!RLookup::contains_key(vc_id));
+        assert!(Lookup::get(DAVE).is_empty());
+        assert!(System::events().iter().any(|record| record.event
+            == Event::verified_credentials(crate::Event::VCRemoved(vc_id))));
+
+        // the removed vc can't be stored again and reused, its tombstone deposit stays reserved
+        assert!(VC::is_removed(vc_id));
+        assert_eq!(VC::deposits(vc_id), Some((DAVE_ACCOUNT_ID, VC_TOMBSTONE_DEPOSIT)));
+        assert_eq!(Balances::reserved_balance(DAVE_ACCOUNT_ID), VC_TOMBSTONE_DEPOSIT);
+        assert_noop!(
+            VC::store(Origin::signed(DAVE_ACCOUNT_ID), vc.encode()),
+            Error::<Test>::VCWasRemoved
+        );
+    })
+}
+
+#[test]
+fn test_inactive_vc_returns_deposit() {
+    new_test_ext().execute_with(|| {
+        let bob_pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            bob_pair.public(),
+            BOB,
+            Vec::new()
+        ));
+
+        let currency_code = convert_to_array::<8>("OTH".into());
+        let token_vc = TokenVC {
+            token_name: convert_to_array::<16>("test".into()),
+            reservable_balance: 1000,
+            decimal: 6,
+            currency_code,
+            max_supply: None,
+        };
+
+        let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+        let vc_type = VCType::TokenVC;
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &BOB, &vec![BOB]));
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_pair.sign(hash.as_ref())],
+            vc_type,
+            owner: BOB,
+            issuers: vec![BOB],
+            is_vc_used: false,
+            vc_property: token_vc,
+        };
+
+        assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
+        let token_vc_id = Lookup::get(BOB)[0];
+
+        let transfer_vc = TokenTransferVC {
+            vc_id: token_vc_id,
+            currency_code,
+            amount: 1_000,
+        };
+
+        let transfer_vc: [u8; 128] = convert_to_array::<128>(transfer_vc.encode());
+        let vc_type = VCType::TokenTransferVC;
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &transfer_vc, &DAVE, &vec![BOB]));
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_pair.sign(hash.as_ref())],
+            vc_type,
+            owner: DAVE,
+            issuers: vec![BOB],
+            is_vc_used: false,
+            vc_property: transfer_vc,
+        };
+
+        assert_ok!(VC::store(Origin::signed(DAVE_ACCOUNT_ID), vc.encode()));
+        let vc_id = Lookup::get(DAVE)[0];
+        assert_eq!(Balances::reserved_balance(DAVE_ACCOUNT_ID), VC_DEPOSIT);
+
+        // marking the vc inactive returns the deposit but the tombstone part
+        assert_ok!(VC::update_status(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            vc_id,
+            VCStatus::Inactive
+        ));
+        assert_eq!(VC::deposits(vc_id), Some((DAVE_ACCOUNT_ID, VC_TOMBSTONE_DEPOSIT)));
+        assert_eq!(Balances::reserved_balance(DAVE_ACCOUNT_ID), VC_TOMBSTONE_DEPOSIT);
+        assert!(VC::is_deactivated(vc_id));
+
+        // the vc can be activated again, it is not removable anymore
+        assert_ok!(VC::update_status(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            vc_id,
+            VCStatus::Active
+        ));
+        assert!(!VC::is_deactivated(vc_id));
+        assert_eq!(VCs::<Test>::get(vc_id).unwrap().1, VCStatus::Active);
+        System::set_block_number(1 + VC_MAX_AGE);
+        assert_noop!(
+            VC::remove_vc(Origin::signed(ALICE_ACCOUNT_ID), vc_id),
+            Error::<Test>::VCStillInUse
+        );
+
+        assert_ok!(VC::update_status(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            vc_id,
+            VCStatus::Inactive
+        ));
+        assert_ok!(VC::remove_vc(Origin::signed(ALICE_ACCOUNT_ID), vc_id));
+        assert!(!VC::is_deactivated(vc_id));
+        assert_eq!(Balances::reserved_balance(DAVE_ACCOUNT_ID), VC_TOMBSTONE_DEPOSIT);
+    })
+}
+
+#[test]
+fn test_vc_waiting_for_signatures_is_not_removable() {
+    new_test_ext().execute_with(|| {
+        let bob_pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            bob_pair.public(),
+            BOB,
+            Vec::new()
+        ));
+
+        let currency_code = convert_to_array::<8>("OTH".into());
+        let token_vc = TokenVC {
+            token_name: convert_to_array::<16>("test".into()),
+            reservable_balance: 1000,
+            decimal: 6,
+            currency_code,
+            max_supply: None,
+        };
+
+        let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+        let vc_type = VCType::TokenVC;
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &BOB, &vec![BOB]));
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_pair.sign(hash.as_ref())],
+            vc_type,
+            owner: BOB,
+            issuers: vec![BOB],
+            is_vc_used: false,
+            vc_property: token_vc,
+        };
+
+        assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
+        let token_vc_id = Lookup::get(BOB)[0];
+
+        let transfer_vc = TokenTransferVC {
+            vc_id: token_vc_id,
+            currency_code,
+            amount: 1_000,
+        };
+
+        // signed by BOB only, still waiting for ALICE's signature
+        let transfer_vc: [u8; 128] = convert_to_array::<128>(transfer_vc.encode());
+        let vc_type = VCType::TokenTransferVC;
+        let issuers = vec![BOB, ALICE];
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &transfer_vc, &DAVE, &issuers));
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![bob_pair.sign(hash.as_ref())],
+            vc_type,
+            owner: DAVE,
+            issuers,
+            is_vc_used: false,
+            vc_property: transfer_vc,
+        };
+
+        assert_ok!(VC::store(Origin::signed(DAVE_ACCOUNT_ID), vc.encode()));
+        let vc_id = Lookup::get(DAVE)[0];
+        assert_eq!(VCs::<Test>::get(vc_id).unwrap().1, VCStatus::Inactive);
+
+        System::set_block_number(1 + VC_MAX_AGE);
+        assert_noop!(
+            VC::remove_vc(Origin::signed(NON_VALIDATOR_ACCOUNT), vc_id),
+            Error::<Test>::VCStillInUse
+        );
+    })
+}
+
+#[test]
+fn test_vc_verifier() {
+    new_test_ext().execute_with(|| {
+        let pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+        let public_key = pair.public();
+
+        // Adding did
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            public_key,
+            BOB,
+            Vec::new()
+        ));
+
+        let token_vc = TokenVC {
+            token_name: convert_to_array::<16>("test".into()),
+            reservable_balance: 1000,
+            decimal: 6,
+            currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
+        };
+
+        let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+        let vc_type = VCType::TokenVC;
+        let owner = BOB;
+        let issuers = vec![BOB];
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+        let signature = pair.sign(hash.as_ref());
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![signature],
+            vc_type,
+            owner,
+            issuers,
+            is_vc_used: false,
+            vc_property: token_vc,
+        };
+
+        assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
+
+        let vc_id = Lookup::get(BOB)[0];
+
+        assert!(<VC as VcVerifier<H256>>::has_active_vc(&BOB, &VCType::TokenVC, &BOB));
+        // wrong type or issuer
+        assert!(!<VC as VcVerifier<H256>>::has_active_vc(&BOB, &VCType::MintTokens, &BOB));
+        assert!(!<VC as VcVerifier<H256>>::has_active_vc(&BOB, &VCType::TokenVC, &ALICE));
+
+        assert_noop!(
+            <VC as VcVerifier<H256>>::get_owned_usable_vc(&ALICE, &vc_id),
+            Error::<Test>::DidNotRegisteredWithVC
+        );
+        let stored_vc = <VC as VcVerifier<H256>>::get_owned_usable_vc(&BOB, &vc_id).unwrap();
+        let decoded: TokenVC = <VC as VcVerifier<H256>>::decode_vc_property(&stored_vc).unwrap();
+        assert_eq!(decoded.decimal, 6);
+
+        // a consumed VC can't be used anymore
+        <VC as VcVerifier<H256>>::consume_vc(&vc_id);
+        assert!(!<VC as VcVerifier<H256>>::has_active_vc(&BOB, &VCType::TokenVC, &BOB));
+        assert_noop!(
+            <VC as VcVerifier<H256>>::get_usable_vc(&vc_id),
+            Error::<Test>::VCAlreadyUsed
+        );
+    })
+}
+
+#[test]
+fn test_ensure_has_credential() {
+    new_test_ext().execute_with(|| {
+        let pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+        let public_key = pair.public();
+
+        // Adding did
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            public_key,
+            BOB,
+            Vec::new()
+        ));
+
+        // no credential yet
+        assert!(EnsureBobCredential::try_origin(Origin::signed(BOB_ACCOUNT_ID)).is_err());
+
+        let token_vc = TokenVC {
+            token_name: convert_to_array::<16>("test".into()),
+            reservable_balance: 1000,
+            decimal: 6,
+            currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
+        };
+
+        let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+        let vc_type = VCType::TokenVC;
+        let owner = BOB;
+        let issuers = vec![BOB];
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+        let signature = pair.sign(hash.as_ref());
+
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![signature],
+            vc_type,
+            owner,
+            issuers,
+            is_vc_used: false,
+            vc_property: token_vc,
+        };
+
+        assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
+        let vc_id = Lookup::get(BOB)[0];
+
+        assert_eq!(EnsureBobCredential::try_origin(Origin::signed(BOB_ACCOUNT_ID)).ok(), Some(BOB_ACCOUNT_ID));
+        // account without a DID or credential, and unsigned origins are rejected
+        assert!(EnsureBobCredential::try_origin(Origin::signed(NON_VALIDATOR_ACCOUNT)).is_err());
+        assert!(EnsureBobCredential::try_origin(Origin::root()).is_err());
+
+        // an inactive credential no longer passes
+        assert_ok!(VC::update_status(Origin::signed(BOB_ACCOUNT_ID), vc_id, VCStatus::Inactive));
+        assert!(EnsureBobCredential::try_origin(Origin::signed(BOB_ACCOUNT_ID)).is_err());
+        assert_ok!(VC::update_status(Origin::signed(BOB_ACCOUNT_ID), vc_id, VCStatus::Active));
+        assert!(EnsureBobCredential::try_origin(Origin::signed(BOB_ACCOUNT_ID)).is_ok());
+
+        // the credential expires `CREDENTIAL_MAX_AGE` blocks after it was stored
+        let created_block_no = System::block_number();
+        System::set_block_number(created_block_no + CREDENTIAL_MAX_AGE - 1);
+        assert!(EnsureBobCredential::try_origin(Origin::signed(BOB_ACCOUNT_ID)).is_ok());
+        System::set_block_number(created_block_no + CREDENTIAL_MAX_AGE);
+        assert!(EnsureBobCredential::try_origin(Origin::signed(BOB_ACCOUNT_ID)).is_err());
+    })
+}
+
+#[test]
+fn test_has_active_vc_requires_issuer_signature() {
+    new_test_ext().execute_with(|| {
+        let bob_pair: sr25519::Pair = sr25519::Pair::from_seed(&BOB_SEED);
+        let dave_pair: sr25519::Pair = sr25519::Pair::from_seed(&DAVE_SEED);
+
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            bob_pair.public(),
+            BOB,
+            Vec::new()
+        ));
+        assert_ok!(Did::add(
+            Origin::signed(ALICE_ACCOUNT_ID),
+            dave_pair.public(),
+            DAVE,
+            Vec::new()
+        ));
+
+        let token_vc = TokenVC {
+            token_name: convert_to_array::<16>("test".into()),
+            reservable_balance: 1000,
+            decimal: 6,
+            currency_code: convert_to_array::<8>("OTH".into()),
+            max_supply: None,
+        };
+
+        let token_vc: [u8; 128] = convert_to_array::<128>(token_vc.encode());
+        let vc_type = VCType::TokenVC;
+        let owner = DAVE;
+        let issuers = vec![DAVE, BOB];
+        let hash = BlakeTwo256::hash_of(&(&vc_type, &token_vc, &owner, &issuers));
+
+        // only DAVE signs the vc naming BOB as co-issuer
+        let vc: verified_credentials::VC<H256> = verified_credentials::VC {
+            hash,
+            signatures: vec![dave_pair.sign(hash.as_ref())],
+            vc_type,
+            owner,
+            issuers,
+            is_vc_used: false,
+            vc_property: token_vc,
+        };
+
+        assert_ok!(VC::store(Origin::signed(BOB_ACCOUNT_ID), vc.encode()));
+        let vc_id = Lookup::get(DAVE)[0];
+
+        // DAVE activates it alone as one of the issuers
+        assert_ok!(VC::update_status(Origin::signed(DAVE_ACCOUNT_ID), vc_id, VCStatus::Active));
+        assert!(<VC as VcVerifier<H256>>::get_usable_vc(&vc_id).is_ok());
+
+        assert!(<VC as VcVerifier<H256>>::has_active_vc(&DAVE, &VCType::TokenVC, &DAVE));
+        assert!(!<VC as VcVerifier<H256>>::has_active_vc(&DAVE, &VCType::TokenVC, &BOB));
+        assert!(EnsureBobCredential::try_origin(Origin::signed(DAVE_ACCOUNT_ID)).is_err());
+    })
+}
@@ -0,0 +1,65 @@
+use super::*;
+
+/// Read and consume VCs from other pallets without touching the vc storage directly
+///
+/// Pallets that need a credential to authorize a call should take an implementation of this
+/// trait as an associated type in their `Config`, the runtime wires it to the vc `Module`.
+pub trait VcVerifier<Hash> {
+    /// Get the VC with the given id if it exists, is active and is not used yet
+    fn get_usable_vc(vc_id: &VCid) -> Result<VC<Hash>, DispatchError>;
+
+    /// Same as `get_usable_vc`, additionally ensuring the VC is owned by `owner`
+    fn get_owned_usable_vc(owner: &Did, vc_id: &VCid) -> Result<VC<Hash>, DispatchError>;
+
+    /// Decode the type specific property of the VC
+    fn decode_vc_property<P: Decode>(vc: &VC<Hash>) -> Result<P, DispatchError>;
+
+    /// Get the owner of the VC referenced by the property of the given VC
+    fn get_linked_vc_owner<P: Decode + HasVCId>(vc: &VC<Hash>) -> Result<Did, DispatchError>;
+
+    /// Check whether `did` holds an active, unused VC of `vc_type` issued and signed by `issuer`
+    fn has_active_vc(did: &Did, vc_type: &VCType, issuer: &Did) -> bool;
+
+    /// Mark the VC as used so it can't authorize anything else
+    fn consume_vc(vc_id: &VCid);
+}
+
+impl<T: Config> VcVerifier<T::Hash> for Module<T> {
+    fn get_usable_vc(vc_id: &VCid) -> Result<VC<T::Hash>, DispatchError> {
+        let (vc, vc_status) = match VCs::<T>::get(vc_id) {
+            Some(vc_details) => vc_details,
+            None => fail!(Error::<T>::VCIdDoesNotExist),
+        };
+        ensure!(vc_status == VCStatus::Active, Error::<T>::VCIsNotActive);
+        ensure!(!vc.is_vc_used, Error::<T>::VCAlreadyUsed);
+        Ok(vc)
+    }
+
+    fn get_owned_usable_vc(owner: &Did, vc_id: &VCid) -> Result<VC<T::Hash>, DispatchError> {
+        let vc = Self::get_usable_vc(vc_id)?;
+        ensure!(vc.owner == *owner, Error::<T>::DidNotRegisteredWithVC);
+        Ok(vc)
+    }
+
+    fn decode_vc_property<P: Decode>(vc: &VC<T::Hash>) -> Result<P, DispatchError> {
+        Self::get_vc::<P>(&vc.vc_property)
+    }
+
+    fn get_linked_vc_owner<P: Decode + HasVCId>(vc: &VC<T::Hash>) -> Result<Did, DispatchError> {
+        let vc_property: P = Self::decode_vc_property(vc)?;
+        match VCs::<T>::get(vc_property.vc_id()) {
+            Some((linked_vc, _)) => Ok(linked_vc.owner),
+            None => fail!(Error::<T>::LinkedVCNotFound),
+        }
+    }
+
+    fn has_active_vc(did: &Did, vc_type: &VCType, issuer: &Did) -> bool {
+        Lookup::get(did)
+            .iter()
+            .any(|vc_id| Self::is_active_vc_from(vc_id, vc_type, issuer))
+    }
+
+    fn consume_vc(vc_id: &VCid) {
+        Self::set_is_used_flag(*vc_id);
+    }
+}
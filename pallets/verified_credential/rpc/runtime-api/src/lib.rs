@@ -0,0 +1,26 @@
+//! Runtime API definition for the Verified Credential pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+use verified_credential::{BatchAnchor, CredentialAnchor, CredentialStatus, Revocation};
+
+sp_api::decl_runtime_apis! {
+    pub trait VerifiedCredentialApi<Hash: Codec, BlockNumber: Codec, Moment: Codec> {
+        /// Get the anchor of a credential issued on its own
+        fn get_anchor(vc_hash: Hash) -> Option<CredentialAnchor<Hash, BlockNumber>>;
+
+        /// Get whether the credential is anchored, revoked or unknown, and whether its schema is retired
+        fn credential_status(vc_hash: Hash) -> CredentialStatus;
+
+        /// Get who revoked the credential, when and why
+        fn get_revocation(vc_hash: Hash) -> Option<Revocation<BlockNumber, Moment>>;
+
+        /// Verify a credential hash against an anchored merkle root with its proof
+        ///
+        /// Returns the batch anchor, with the issuer DID and anchoring block, if the proof is valid
+        fn verify_batch(root: Hash, vc_hash: Hash, proof: Vec<Hash>) -> Option<BatchAnchor<Hash, BlockNumber>>;
+    }
+}
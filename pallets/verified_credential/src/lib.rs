@@ -1,49 +1,293 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use did::Did;
 use frame_support::{
-    debug, decl_event, decl_module, decl_storage, dispatch::DispatchResult, StorageMap,
+    debug, decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::{Get, Time}, weights::Weight, StorageMap, StorageValue,
 };
 use frame_system::{self, ensure_signed};
+use sp_runtime::DispatchError;
 use sp_std::prelude::*;
 
+#[cfg(test)]
+mod tests;
+pub mod merkle;
+mod migration;
+mod structs;
+pub use crate::structs::*;
+
 /// The VC trait
-pub trait Config: frame_system::Config {
+pub trait Config: frame_system::Config + schema::Config {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+    /// Source of the timestamps recorded on revocation
+    type Time: Time;
+
+    /// Maximum length of the reason given when revoking a credential
+    type MaxReasonLength: Get<u32>;
 }
 
+pub type MomentOf<T> = <<T as Config>::Time as Time>::Moment;
+
 decl_event!(
     //Events for verified_credentials
-    pub enum Event<T> where <T as frame_system::Config>::AccountId, <T as frame_system::Config>::Hash {
-        // new verified credential issued
-        VCIssued(AccountId, Hash, Hash),
+    pub enum Event<T> where <T as frame_system::Config>::Hash, Moment = MomentOf<T> {
+        /// new verified credential anchored (issuer, schema, vc_hash)
+        VCIssued(Did, Hash, Hash),
+        /// verified credential revoked by its issuer or a validator (revoked_by, vc_hash, timestamp)
+        VCRevoked(Did, Hash, Moment),
+        /// merkle root of a batch of verified credentials anchored (issuer, schema, root, leaf_count)
+        BatchAnchored(Did, Hash, Hash, u32),
     }
 );
 
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// Caller has no registered DID
+        DIDNotRegistered,
+        /// Schema does not exist
+        SchemaNotFound,
+        /// Schema is retired and can't be used for new credentials
+        SchemaRetired,
+        /// Credential hash is already anchored
+        VCAlreadyAnchored,
+        /// Credential hash is not anchored
+        VCNotFound,
+        /// Only the issuer of the credential or a validator can perform this operation
+        NotIssuer,
+        /// Credential is already revoked
+        VCAlreadyRevoked,
+        /// Batch must contain at least one credential
+        EmptyBatch,
+        /// Merkle root is already anchored
+        BatchAlreadyAnchored,
+        /// Revocation reason exceeds the maximum length
+        ReasonTooLong,
+        /// Merkle root is not anchored or the proof doesn't match the credential hash
+        InvalidBatchProof,
+    }
+}
+
 decl_module! {
     // runtime module for VC issuance
     pub struct Module<T: Config> for enum Call where origin: T::Origin {
         fn deposit_event() = default;
+        type Error = Error<T>;
+
+        const MaxReasonLength: u32 = T::MaxReasonLength::get();
 
         /// Issue a new VC and insert the hash on chain
-        /// origin - VC issuer
-        /// schema - hash of vc schema
+        /// origin - VC issuer, must have a registered DID
+        /// schema - hash of vc schema, must exist and not be retired
         /// vc_hash - hash of the vc issued
         #[weight = 1]
         pub fn add(origin, schema: T::Hash, vc_hash: T::Hash) -> DispatchResult {
             let sender = ensure_signed(origin)?;
+            let issuer = Self::ensure_issuer_did(&sender)?;
+
+            Self::ensure_schema_usable(&schema)?;
+            ensure!(!VC::<T>::contains_key(vc_hash), Error::<T>::VCAlreadyAnchored);
 
             debug::RuntimeLogger::init();
             debug::print!("Here- Issuing VC");
-            <VC<T>>::insert(vc_hash, (sender.clone(), schema));
+            <VC<T>>::insert(vc_hash, CredentialAnchor {
+                issuer,
+                schema,
+                anchored_at: <frame_system::Module<T>>::block_number(),
+                revoked: false,
+            });
 
-            Self::deposit_event(RawEvent::VCIssued(sender, schema, vc_hash));
+            Self::deposit_event(RawEvent::VCIssued(issuer, schema, vc_hash));
             Ok(())
         }
+
+        /// Revoke an anchored VC
+        /// origin - issuer of the VC or a validator
+        /// vc_hash - hash of the vc to revoke
+        /// reason - why the vc is revoked, shown to verifiers
+        #[weight = 1]
+        pub fn revoke(origin, vc_hash: T::Hash, reason: Vec<u8>) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(reason.len() <= T::MaxReasonLength::get() as usize, Error::<T>::ReasonTooLong);
+            let sender_did = did::Module::<T>::get_did_from_account_id(&sender);
+            let is_validator = did::Module::<T>::is_caller_validator(&sender);
+
+            VC::<T>::try_mutate(vc_hash, |anchor| -> DispatchResult {
+                let anchor = anchor.as_mut().ok_or(Error::<T>::VCNotFound)?;
+                ensure!(anchor.issuer == sender_did || is_validator, Error::<T>::NotIssuer);
+                ensure!(
+                    !anchor.revoked && !Revocations::<T>::contains_key(vc_hash),
+                    Error::<T>::VCAlreadyRevoked
+                );
+                anchor.revoked = true;
+                Ok(())
+            })?;
+
+            Self::record_revocation(sender_did, vc_hash, reason);
+            Ok(())
+        }
+
+        /// Revoke a VC anchored as part of a batch
+        /// origin - issuer of the batch or a validator
+        /// root - merkle root of the batch
+        /// vc_hash - hash of the vc to revoke
+        /// proof - merkle proof of the vc in the batch, see `merkle::merkle_proof`
+        /// reason - why the vc is revoked, shown to verifiers
+        #[weight = 1]
+        pub fn revoke_batched(
+            origin,
+            root: T::Hash,
+            vc_hash: T::Hash,
+            proof: Vec<T::Hash>,
+            reason: Vec<u8>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            ensure!(reason.len() <= T::MaxReasonLength::get() as usize, Error::<T>::ReasonTooLong);
+            let sender_did = did::Module::<T>::get_did_from_account_id(&sender);
+            let is_validator = did::Module::<T>::is_caller_validator(&sender);
+
+            ensure!(!Revocations::<T>::contains_key(vc_hash), Error::<T>::VCAlreadyRevoked);
+            let anchor = Self::verify_batch(&root, &vc_hash, &proof)
+                .ok_or(Error::<T>::InvalidBatchProof)?;
+            ensure!(anchor.issuer == sender_did || is_validator, Error::<T>::NotIssuer);
+
+            Self::record_revocation(sender_did, vc_hash, reason);
+            Ok(())
+        }
+
+        /// Anchor the merkle root of a batch of VCs issued against the same schema
+        /// origin - VC issuer, must have a registered DID
+        /// schema - hash of vc schema, must exist and not be retired
+        /// root - merkle root of the vc hashes, see `merkle::merkle_root`
+        /// leaf_count - number of vc hashes in the batch
+        #[weight = 1]
+        pub fn add_batch(origin, schema: T::Hash, root: T::Hash, leaf_count: u32) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let issuer = Self::ensure_issuer_did(&sender)?;
+
+            Self::ensure_schema_usable(&schema)?;
+            ensure!(leaf_count > 0, Error::<T>::EmptyBatch);
+            ensure!(!BatchRoots::<T>::contains_key(root), Error::<T>::BatchAlreadyAnchored);
+
+            <BatchRoots<T>>::insert(root, BatchAnchor {
+                issuer,
+                schema,
+                anchored_at: <frame_system::Module<T>>::block_number(),
+                leaf_count,
+            });
+
+            Self::deposit_event(RawEvent::BatchAnchored(issuer, schema, root, leaf_count));
+            Ok(())
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migration::migrate::<T>()
+        }
     }
 }
 
+// NOTE: up to V1 the anchors were stored under the `VC` prefix of the vc pallet, see `migration`
 decl_storage! {
-    trait Store for Module<T: Config> as VC {
-        VC get(fn vc): map hasher(opaque_blake2_256) T::Hash => Option<(T::AccountId, T::Hash)>;
+    trait Store for Module<T: Config> as VerifiedCredential {
+        VC get(fn vc): map hasher(opaque_blake2_256) T::Hash => Option<CredentialAnchor<T::Hash, T::BlockNumber>>;
+        Revocations get(fn revocation): map hasher(opaque_blake2_256) T::Hash => Option<Revocation<T::BlockNumber, MomentOf<T>>>;
+        BatchRoots get(fn batch_root): map hasher(opaque_blake2_256) T::Hash => Option<BatchAnchor<T::Hash, T::BlockNumber>>;
+        AnchorsVersion: AnchorPalletVersion = AnchorPalletVersion::V1_0_0;
+    }
+    add_extra_genesis {
+        build(|_config: &GenesisConfig| {
+            // a new chain starts with the latest storage layout
+            AnchorsVersion::put(AnchorPalletVersion::V2_0_0);
+        })
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Ensure the account has a registered DID and return it
+    fn ensure_issuer_did(account: &T::AccountId) -> Result<Did, DispatchError> {
+        ensure!(did::Module::<T>::does_did_exist(account), Error::<T>::DIDNotRegistered);
+        Ok(did::Module::<T>::get_did_from_account_id(account))
+    }
+
+    /// Ensure the schema exists and can still be used to issue credentials
+    fn ensure_schema_usable(schema: &T::Hash) -> DispatchResult {
+        match schema::Module::<T>::schema_status(schema) {
+            None => Err(Error::<T>::SchemaNotFound.into()),
+            Some(schema::SchemaStatus::Retired) => Err(Error::<T>::SchemaRetired.into()),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Store the revocation details of the credential and notify verifiers
+    fn record_revocation(revoked_by: Did, vc_hash: T::Hash, reason: Vec<u8>) {
+        let timestamp = T::Time::now();
+        Revocations::<T>::insert(vc_hash, Revocation {
+            revoked_by,
+            reason,
+            revoked_at: <frame_system::Module<T>>::block_number(),
+            timestamp,
+        });
+
+        Self::deposit_event(RawEvent::VCRevoked(revoked_by, vc_hash, timestamp));
+    }
+
+    /// Check whether the credential is anchored and not revoked
+    ///
+    /// Credentials of a retired schema still verify, `credential_status` flags them
+    pub fn verify(vc_hash: &T::Hash) -> bool {
+        match Self::credential_status(vc_hash) {
+            CredentialStatus::Anchored | CredentialStatus::SchemaRetired => true,
+            CredentialStatus::Unknown | CredentialStatus::Revoked => false,
+        }
+    }
+
+    /// Get the status of the credential, see `Revocations` for the details of a revocation
+    ///
+    /// A credential of a batch is `Unknown` unless it was revoked, use `verify_batch` with its proof
+    pub fn credential_status(vc_hash: &T::Hash) -> CredentialStatus {
+        if Revocations::<T>::contains_key(vc_hash) {
+            return CredentialStatus::Revoked;
+        }
+        match VC::<T>::get(vc_hash) {
+            None => CredentialStatus::Unknown,
+            Some(anchor) if anchor.revoked => CredentialStatus::Revoked,
+            Some(anchor) if schema::Module::<T>::is_schema_retired(&anchor.schema) => {
+                CredentialStatus::SchemaRetired
+            }
+            Some(_) => CredentialStatus::Anchored,
+        }
+    }
+
+    /// Check that the credential hash is part of an anchored batch using its merkle proof
+    ///
+    /// Returns the batch anchor, giving the issuer DID and anchoring block, if the root is anchored,
+    /// the proof is valid and the credential is not revoked
+    pub fn verify_batch(
+        root: &T::Hash,
+        vc_hash: &T::Hash,
+        proof: &[T::Hash],
+    ) -> Option<BatchAnchor<T::Hash, T::BlockNumber>> {
+        if Revocations::<T>::contains_key(vc_hash) {
+            return None;
+        }
+        let anchor = BatchRoots::<T>::get(root)?;
+        // a proof can't be longer than the height of the tree
+        let max_depth = 32 - anchor.leaf_count.saturating_sub(1).leading_zeros();
+        if proof.len() > max_depth as usize {
+            return None;
+        }
+        if merkle::verify_proof::<T::Hashing>(root, vc_hash, proof) {
+            Some(anchor)
+        } else {
+            None
+        }
+    }
+
+    /// Check whether the VC was issued against a schema that has since been retired
+    ///
+    /// Returns `None` if the VC is unknown
+    pub fn uses_retired_schema(vc_hash: &T::Hash) -> Option<bool> {
+        let anchor = VC::<T>::get(vc_hash)?;
+        Some(schema::Module::<T>::is_schema_retired(&anchor.schema))
     }
 }
@@ -0,0 +1,82 @@
+//! Binary Merkle tree over credential hashes used for batch anchoring.
+//!
+//! Each credential hash is hashed again with a leaf prefix, and each parent is the hash of its two
+//! children concatenated in ascending order with a node prefix, so a proof is just the list of
+//! sibling hashes from the leaf up to the root. The prefixes keep an internal node from being
+//! presented as a credential. A node without a sibling is carried up to the next level unchanged.
+
+use sp_runtime::traits::Hash;
+use sp_std::{prelude::*, vec};
+
+/// Domain prefix of the leaves
+const LEAF_PREFIX: u8 = 0;
+/// Domain prefix of the internal nodes
+const NODE_PREFIX: u8 = 1;
+
+/// Hash a credential hash into a leaf of the tree
+fn hash_leaf<H: Hash>(leaf: &H::Output) -> H::Output {
+    let mut data = vec![LEAF_PREFIX];
+    data.extend_from_slice(leaf.as_ref());
+    <H as Hash>::hash(&data)
+}
+
+/// Hash two nodes in ascending order
+fn hash_pair<H: Hash>(a: &H::Output, b: &H::Output) -> H::Output {
+    let (first, second) = if a.as_ref() <= b.as_ref() { (a, b) } else { (b, a) };
+    let mut data = vec![NODE_PREFIX];
+    data.extend_from_slice(first.as_ref());
+    data.extend_from_slice(second.as_ref());
+    <H as Hash>::hash(&data)
+}
+
+/// Compute the root of the tree built from the given leaves, `None` if there are none
+pub fn merkle_root<H: Hash>(leaves: &[H::Output]) -> Option<H::Output> {
+    let mut level: Vec<H::Output> = leaves.iter().map(hash_leaf::<H>).collect();
+    if level.is_empty() {
+        return None;
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair::<H>(a, b),
+                [a] => *a,
+                _ => unreachable!("chunks of 2 are never empty; qed"),
+            })
+            .collect();
+    }
+    level.pop()
+}
+
+/// Build the proof of the leaf at `index`, `None` if the index is out of bounds
+pub fn merkle_proof<H: Hash>(leaves: &[H::Output], mut index: usize) -> Option<Vec<H::Output>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut level: Vec<H::Output> = leaves.iter().map(hash_leaf::<H>).collect();
+    let mut proof = Vec::new();
+    while level.len() > 1 {
+        let sibling = index ^ 1;
+        if let Some(node) = level.get(sibling) {
+            proof.push(*node);
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair::<H>(a, b),
+                [a] => *a,
+                _ => unreachable!("chunks of 2 are never empty; qed"),
+            })
+            .collect();
+        index /= 2;
+    }
+    Some(proof)
+}
+
+/// Check that `leaf` is part of the tree with the given root
+pub fn verify_proof<H: Hash>(root: &H::Output, leaf: &H::Output, proof: &[H::Output]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(hash_leaf::<H>(leaf), |node, sibling| hash_pair::<H>(&node, sibling));
+    computed == *root
+}
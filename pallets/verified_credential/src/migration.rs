@@ -0,0 +1,36 @@
+use super::*;
+use frame_support::storage::migration::{put_storage_value, StorageIterator};
+
+pub fn migrate<T: Config>() -> frame_support::weights::Weight {
+    frame_support::debug::RuntimeLogger::init();
+    // Storage migrations should use storage versions for safety.
+    match AnchorsVersion::get() {
+        AnchorPalletVersion::V1_0_0 => {
+            // The V1 anchors were stored under the `VC` prefix shared with the vc pallet. They are
+            // keyed with an opaque hasher, so the values are moved under their raw keys instead of
+            // being translated by key.
+            let old_anchors: Vec<_> =
+                StorageIterator::<(T::AccountId, T::Hash)>::new(b"VC", b"VC").drain().collect();
+            let now = <frame_system::Module<T>>::block_number();
+            for (raw_key, (issuer, schema)) in old_anchors.iter() {
+                let anchor = CredentialAnchor {
+                    issuer: did::Module::<T>::get_did_from_account_id(issuer),
+                    schema: *schema,
+                    anchored_at: now,
+                    revoked: false,
+                };
+                put_storage_value(b"VerifiedCredential", b"VC", raw_key, anchor);
+            }
+            // Update storage version.
+            AnchorsVersion::put(AnchorPalletVersion::V2_0_0);
+
+            let count = old_anchors.len() as Weight;
+            // Return the weight consumed by the migration.
+            T::DbWeight::get().reads_writes(2 * count + 1, count + 1)
+        }
+        _ => {
+            frame_support::debug::info!(" >>> Unused migration!");
+            0
+        }
+    }
+}
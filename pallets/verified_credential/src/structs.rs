@@ -0,0 +1,64 @@
+use super::*;
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+
+/// Credential hash anchored on chain by an issuer
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct CredentialAnchor<Hash, BlockNumber> {
+    /// DID of the issuer that anchored the credential
+    pub issuer: Did,
+    /// Hash of the schema the credential was issued against
+    pub schema: Hash,
+    /// Block in which the credential was anchored
+    pub anchored_at: BlockNumber,
+    /// Whether the credential was revoked, details are kept in `Revocations`
+    pub revoked: bool,
+}
+
+/// Details of the revocation of an anchored credential
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct Revocation<BlockNumber, Moment> {
+    /// DID of the issuer or validator that revoked the credential
+    pub revoked_by: Did,
+    /// Reason given for the revocation
+    pub reason: Vec<u8>,
+    /// Block in which the credential was revoked
+    pub revoked_at: BlockNumber,
+    /// Timestamp of the block in which the credential was revoked
+    pub timestamp: Moment,
+}
+
+/// Status of a credential hash as seen by verifiers
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum CredentialStatus {
+    /// The hash was never anchored
+    Unknown,
+    /// The hash is anchored and still valid
+    Anchored,
+    /// The hash was anchored and later revoked
+    Revoked,
+    /// The hash is anchored and not revoked, but its schema has since been retired
+    SchemaRetired,
+}
+
+/// Merkle root of a batch of credential hashes anchored on chain by an issuer
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct BatchAnchor<Hash, BlockNumber> {
+    /// DID of the issuer that anchored the batch
+    pub issuer: Did,
+    /// Hash of the schema every credential of the batch was issued against
+    pub schema: Hash,
+    /// Block in which the batch was anchored
+    pub anchored_at: BlockNumber,
+    /// Number of credential hashes in the batch
+    pub leaf_count: u32,
+}
+
+/// Utility type for managing upgrades/migrations.
+#[derive(Encode, Decode, Clone, PartialEq, RuntimeDebug)]
+pub enum AnchorPalletVersion {
+    /// Anchors stored as (AccountId, schema hash)
+    V1_0_0,
+    /// Anchors stored as `CredentialAnchor`
+    V2_0_0,
+}
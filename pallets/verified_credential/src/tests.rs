@@ -0,0 +1,560 @@
+// Tests for verified_credential module
+use super::*;
+use crate as verified_credential;
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok, parameter_types,
+    storage::migration::{get_storage_value, put_storage_value},
+    traits::{OnRuntimeUpgrade, StorageMapShim},
+};
+use sp_core::{sr25519, H256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, Hash},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Module, Call, Config, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
+        Did: did::{Module, Call, Storage, Event, Config},
+        ValidatorSet: validator_set::{Module, Call, Storage, Event, Config},
+        Balances: balances::{Module, Call, Storage, Event<T>, Config<T>},
+        Schema: schema::{Module, Call, Storage, Event<T>},
+        VerifiedCredential: verified_credential::{Module, Call, Storage, Event<T>, Config},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = Did;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+}
+
+impl did::Config for Test {
+    type Event = Event;
+}
+
+impl validator_set::Config for Test {
+    type Event = Event;
+    type ApproveOrigin = frame_system::EnsureRoot<u64>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = StorageMapShim<
+        balances::Account<Test>,
+        frame_system::Provider<Test>,
+        u64,
+        balances::AccountData<u64>,
+    >;
+    type MaxLocks = MaxLocks;
+    type WeightInfo = ();
+    type DidResolution = did::Module<Test>;
+}
+
+parameter_types! {
+    pub const MaxSchemaLength: u32 = 1024;
+    pub const SchemaDepositBase: u64 = 10;
+    pub const SchemaDepositPerByte: u64 = 1;
+}
+
+impl schema::Config for Test {
+    type Event = Event;
+    type MaxSchemaLength = MaxSchemaLength;
+    type Currency = Balances;
+    type SchemaDepositBase = SchemaDepositBase;
+    type SchemaDepositPerByte = SchemaDepositPerByte;
+}
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Config for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MaxReasonLength: u32 = 16;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type Time = Timestamp;
+    type MaxReasonLength = MaxReasonLength;
+}
+
+const VALIDATOR_ACCOUNT: u64 = 0;
+const VALIDATOR_DID: did::Did = *b"did:ssid:validator\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const VALIDATOR_PUBKEY: sr25519::Public = sr25519::Public([0; 32]);
+const ISSUER_ACCOUNT: u64 = 217020518514230019;
+const ISSUER_DID: did::Did = *b"did:ssid:issuer\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const ISSUER_PUBKEY: sr25519::Public = sr25519::Public([3; 32]);
+const NO_DID_ACCOUNT: u64 = 2;
+const TEST_SCHEMA: &str = r#"{"type": "object"}"#;
+
+// This function basically just builds a genesis storage key/value store according to
+// our desired mockup.
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut o = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    did::GenesisConfig {
+        dids: vec![
+            did::DidStruct {
+                identifier: VALIDATOR_DID,
+                public_key: VALIDATOR_PUBKEY,
+                metadata: vec![],
+            },
+            did::DidStruct {
+                identifier: ISSUER_DID,
+                public_key: ISSUER_PUBKEY,
+                metadata: vec![],
+            },
+        ],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    validator_set::GenesisConfig {
+        validators: vec![VALIDATOR_DID],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    balances::GenesisConfig::<Test> {
+        balances: vec![(VALIDATOR_ACCOUNT, 1_000), (ISSUER_ACCOUNT, 1_000)],
+    }
+    .assimilate_storage(&mut o)
+    .unwrap();
+
+    verified_credential::GenesisConfig::default()
+        .assimilate_storage(&mut o)
+        .unwrap();
+
+    let mut ext: sp_io::TestExternalities = o.into();
+    ext.execute_with(|| System::set_block_number(1));
+    ext
+}
+
+fn add_schema() -> H256 {
+    assert_ok!(Schema::add(
+        Origin::signed(VALIDATOR_ACCOUNT),
+        b"Test".to_vec(),
+        schema::SchemaVersion::default(),
+        None,
+        TEST_SCHEMA.as_bytes().to_vec()
+    ));
+    BlakeTwo256::hash(TEST_SCHEMA.as_bytes())
+}
+
+#[test]
+fn test_anchor_credential() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+
+        assert_ok!(VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), schema, vc_hash));
+        assert_eq!(
+            VerifiedCredential::vc(vc_hash),
+            Some(CredentialAnchor {
+                issuer: ISSUER_DID,
+                schema,
+                anchored_at: 1,
+                revoked: false,
+            })
+        );
+        assert!(VerifiedCredential::verify(&vc_hash));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::verified_credential(RawEvent::VCIssued(ISSUER_DID, schema, vc_hash))
+        );
+
+        // duplicates are rejected
+        assert_noop!(
+            VerifiedCredential::add(Origin::signed(VALIDATOR_ACCOUNT), schema, vc_hash),
+            Error::<Test>::VCAlreadyAnchored
+        );
+    })
+}
+
+#[test]
+fn test_anchor_requires_did_and_schema() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+
+        assert_noop!(
+            VerifiedCredential::add(Origin::signed(NO_DID_ACCOUNT), schema, vc_hash),
+            Error::<Test>::DIDNotRegistered
+        );
+        assert_noop!(
+            VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), H256::zero(), vc_hash),
+            Error::<Test>::SchemaNotFound
+        );
+
+        assert_ok!(Schema::update_status(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            schema,
+            schema::SchemaStatus::Retired
+        ));
+        assert_noop!(
+            VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), schema, vc_hash),
+            Error::<Test>::SchemaRetired
+        );
+    })
+}
+
+#[test]
+fn test_revoke_credential() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+        assert_eq!(VerifiedCredential::credential_status(&vc_hash), CredentialStatus::Unknown);
+        assert_ok!(VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), schema, vc_hash));
+        assert_eq!(VerifiedCredential::credential_status(&vc_hash), CredentialStatus::Anchored);
+
+        assert_noop!(
+            VerifiedCredential::revoke(Origin::signed(NO_DID_ACCOUNT), vc_hash, vec![]),
+            Error::<Test>::NotIssuer
+        );
+        assert_noop!(
+            VerifiedCredential::revoke(Origin::signed(ISSUER_ACCOUNT), H256::zero(), vec![]),
+            Error::<Test>::VCNotFound
+        );
+        assert_noop!(
+            VerifiedCredential::revoke(Origin::signed(ISSUER_ACCOUNT), vc_hash, vec![0; 17]),
+            Error::<Test>::ReasonTooLong
+        );
+
+        Timestamp::set_timestamp(42);
+        assert_ok!(VerifiedCredential::revoke(
+            Origin::signed(ISSUER_ACCOUNT),
+            vc_hash,
+            b"expired".to_vec()
+        ));
+        assert!(!VerifiedCredential::verify(&vc_hash));
+        assert_eq!(VerifiedCredential::credential_status(&vc_hash), CredentialStatus::Revoked);
+        assert_eq!(
+            VerifiedCredential::revocation(vc_hash),
+            Some(Revocation {
+                revoked_by: ISSUER_DID,
+                reason: b"expired".to_vec(),
+                revoked_at: 1,
+                timestamp: 42,
+            })
+        );
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::verified_credential(RawEvent::VCRevoked(ISSUER_DID, vc_hash, 42))
+        );
+
+        assert_noop!(
+            VerifiedCredential::revoke(Origin::signed(ISSUER_ACCOUNT), vc_hash, vec![]),
+            Error::<Test>::VCAlreadyRevoked
+        );
+    })
+}
+
+#[test]
+fn validator_should_revoke_credential() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+        assert_ok!(VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), schema, vc_hash));
+
+        assert_ok!(VerifiedCredential::revoke(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            vc_hash,
+            b"fraud".to_vec()
+        ));
+        assert_eq!(VerifiedCredential::credential_status(&vc_hash), CredentialStatus::Revoked);
+        assert_eq!(
+            VerifiedCredential::revocation(vc_hash).map(|revocation| revocation.revoked_by),
+            Some(VALIDATOR_DID)
+        );
+    })
+}
+
+#[test]
+fn test_retired_schema_is_flagged() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+        assert_ok!(VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), schema, vc_hash));
+        assert_eq!(VerifiedCredential::uses_retired_schema(&vc_hash), Some(false));
+        assert_eq!(VerifiedCredential::credential_status(&vc_hash), CredentialStatus::Anchored);
+
+        assert_ok!(Schema::update_status(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            schema,
+            schema::SchemaStatus::Retired
+        ));
+        assert_eq!(VerifiedCredential::uses_retired_schema(&vc_hash), Some(true));
+        // verifiers see the flag in the status, the credential itself stays valid
+        assert_eq!(VerifiedCredential::credential_status(&vc_hash), CredentialStatus::SchemaRetired);
+        assert!(VerifiedCredential::verify(&vc_hash));
+        assert_eq!(VerifiedCredential::uses_retired_schema(&H256::zero()), None);
+    })
+}
+
+#[test]
+fn test_batch_anchor() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let leaves: Vec<H256> = (0u8..5).map(|i| BlakeTwo256::hash(&[i])).collect();
+        let root = merkle::merkle_root::<BlakeTwo256>(&leaves).unwrap();
+
+        assert_noop!(
+            VerifiedCredential::add_batch(Origin::signed(ISSUER_ACCOUNT), schema, root, 0),
+            Error::<Test>::EmptyBatch
+        );
+        assert_ok!(VerifiedCredential::add_batch(
+            Origin::signed(ISSUER_ACCOUNT),
+            schema,
+            root,
+            leaves.len() as u32
+        ));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::verified_credential(RawEvent::BatchAnchored(ISSUER_DID, schema, root, 5))
+        );
+        assert_noop!(
+            VerifiedCredential::add_batch(Origin::signed(ISSUER_ACCOUNT), schema, root, 5),
+            Error::<Test>::BatchAlreadyAnchored
+        );
+
+        let expected = BatchAnchor {
+            issuer: ISSUER_DID,
+            schema,
+            anchored_at: 1,
+            leaf_count: 5,
+        };
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = merkle::merkle_proof::<BlakeTwo256>(&leaves, index).unwrap();
+            assert_eq!(
+                VerifiedCredential::verify_batch(&root, leaf, &proof),
+                Some(expected.clone())
+            );
+        }
+
+        // wrong leaf, wrong proof and unknown root
+        let proof = merkle::merkle_proof::<BlakeTwo256>(&leaves, 0).unwrap();
+        assert_eq!(VerifiedCredential::verify_batch(&root, &H256::zero(), &proof), None);
+        assert_eq!(VerifiedCredential::verify_batch(&root, &leaves[1], &proof), None);
+        assert_eq!(VerifiedCredential::verify_batch(&H256::zero(), &leaves[0], &proof), None);
+
+        // an internal node is not accepted as a credential of the batch
+        let node = merkle::merkle_root::<BlakeTwo256>(&leaves[..2]).unwrap();
+        assert_eq!(VerifiedCredential::verify_batch(&root, &node, &proof[1..]), None);
+    })
+}
+
+#[test]
+fn test_upgrade_keeps_anchors_of_new_chain() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(AnchorsVersion::get(), AnchorPalletVersion::V2_0_0);
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+        assert_ok!(VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), schema, vc_hash));
+        let anchor = VerifiedCredential::vc(vc_hash);
+
+        System::set_block_number(5);
+        VerifiedCredential::on_runtime_upgrade();
+        assert_eq!(VerifiedCredential::vc(vc_hash), anchor);
+        assert_eq!(AnchorsVersion::get(), AnchorPalletVersion::V2_0_0);
+    })
+}
+
+#[test]
+fn test_migrate_v1_anchors() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+        // V1 anchors are stored as (issuer account, schema) under the prefix of the vc pallet
+        let raw_key = sp_io::hashing::blake2_256(&vc_hash.encode());
+        put_storage_value(b"VC", b"VC", &raw_key, (ISSUER_ACCOUNT, schema));
+        AnchorsVersion::put(AnchorPalletVersion::V1_0_0);
+
+        System::set_block_number(5);
+        VerifiedCredential::on_runtime_upgrade();
+        assert_eq!(AnchorsVersion::get(), AnchorPalletVersion::V2_0_0);
+        assert_eq!(get_storage_value::<(u64, H256)>(b"VC", b"VC", &raw_key), None);
+        let anchor = Some(CredentialAnchor {
+            issuer: ISSUER_DID,
+            schema,
+            anchored_at: 5,
+            revoked: false,
+        });
+        assert_eq!(VerifiedCredential::vc(vc_hash), anchor);
+        assert!(VerifiedCredential::verify(&vc_hash));
+
+        // the migration only runs once
+        System::set_block_number(6);
+        VerifiedCredential::on_runtime_upgrade();
+        assert_eq!(VerifiedCredential::vc(vc_hash), anchor);
+    })
+}
+
+#[test]
+fn test_revocation_survives_upgrade() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let vc_hash = BlakeTwo256::hash(b"credential");
+        assert_ok!(VerifiedCredential::add(Origin::signed(ISSUER_ACCOUNT), schema, vc_hash));
+        assert_ok!(VerifiedCredential::revoke(
+            Origin::signed(ISSUER_ACCOUNT),
+            vc_hash,
+            b"expired".to_vec()
+        ));
+        let revocation = VerifiedCredential::revocation(vc_hash);
+
+        VerifiedCredential::on_runtime_upgrade();
+        assert_eq!(VerifiedCredential::credential_status(&vc_hash), CredentialStatus::Revoked);
+        assert!(!VerifiedCredential::verify(&vc_hash));
+        assert_eq!(VerifiedCredential::revocation(vc_hash), revocation);
+    })
+}
+
+#[test]
+fn test_revoke_batched_credential() {
+    new_test_ext().execute_with(|| {
+        let schema = add_schema();
+        let leaves: Vec<H256> = (0u8..5).map(|i| BlakeTwo256::hash(&[i])).collect();
+        let root = merkle::merkle_root::<BlakeTwo256>(&leaves).unwrap();
+        assert_ok!(VerifiedCredential::add_batch(
+            Origin::signed(ISSUER_ACCOUNT),
+            schema,
+            root,
+            leaves.len() as u32
+        ));
+        let proof = merkle::merkle_proof::<BlakeTwo256>(&leaves, 2).unwrap();
+        assert_eq!(VerifiedCredential::credential_status(&leaves[2]), CredentialStatus::Unknown);
+
+        assert_noop!(
+            VerifiedCredential::revoke_batched(
+                Origin::signed(NO_DID_ACCOUNT),
+                root,
+                leaves[2],
+                proof.clone(),
+                vec![]
+            ),
+            Error::<Test>::NotIssuer
+        );
+        assert_noop!(
+            VerifiedCredential::revoke_batched(
+                Origin::signed(ISSUER_ACCOUNT),
+                root,
+                leaves[3],
+                proof.clone(),
+                vec![]
+            ),
+            Error::<Test>::InvalidBatchProof
+        );
+        assert_noop!(
+            VerifiedCredential::revoke_batched(
+                Origin::signed(ISSUER_ACCOUNT),
+                root,
+                leaves[2],
+                proof.clone(),
+                vec![0; 17]
+            ),
+            Error::<Test>::ReasonTooLong
+        );
+
+        Timestamp::set_timestamp(42);
+        assert_ok!(VerifiedCredential::revoke_batched(
+            Origin::signed(ISSUER_ACCOUNT),
+            root,
+            leaves[2],
+            proof.clone(),
+            b"expired".to_vec()
+        ));
+        assert_eq!(VerifiedCredential::verify_batch(&root, &leaves[2], &proof), None);
+        assert_eq!(VerifiedCredential::credential_status(&leaves[2]), CredentialStatus::Revoked);
+        assert!(!VerifiedCredential::verify(&leaves[2]));
+        assert_eq!(
+            VerifiedCredential::revocation(leaves[2]),
+            Some(Revocation {
+                revoked_by: ISSUER_DID,
+                reason: b"expired".to_vec(),
+                revoked_at: 1,
+                timestamp: 42,
+            })
+        );
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::verified_credential(RawEvent::VCRevoked(ISSUER_DID, leaves[2], 42))
+        );
+        assert_noop!(
+            VerifiedCredential::revoke_batched(
+                Origin::signed(VALIDATOR_ACCOUNT),
+                root,
+                leaves[2],
+                proof,
+                vec![]
+            ),
+            Error::<Test>::VCAlreadyRevoked
+        );
+
+        // the rest of the batch stays valid
+        let proof = merkle::merkle_proof::<BlakeTwo256>(&leaves, 1).unwrap();
+        assert!(VerifiedCredential::verify_batch(&root, &leaves[1], &proof).is_some());
+        assert_ok!(VerifiedCredential::revoke_batched(
+            Origin::signed(VALIDATOR_ACCOUNT),
+            root,
+            leaves[1],
+            proof,
+            b"fraud".to_vec()
+        ));
+        assert_eq!(
+            VerifiedCredential::revocation(leaves[1]).map(|revocation| revocation.revoked_by),
+            Some(VALIDATOR_DID)
+        );
+    })
+}
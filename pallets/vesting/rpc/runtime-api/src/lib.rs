@@ -0,0 +1,20 @@
+//! Runtime API definition for the Vesting pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+
+use codec::Codec;
+use did::Did;
+use sp_std::prelude::*;
+use tokens::{CurrencyCode, TokenBalance};
+use vesting::VestingSchedule;
+
+sp_api::decl_runtime_apis! {
+    pub trait VestingApi<BlockNumber: Codec> {
+        /// Get the vesting schedules of the DID for the token
+        fn vesting_schedules(did: Did, currency_code: CurrencyCode) -> Vec<VestingSchedule<BlockNumber>>;
+
+        /// Get the amount of the token still locked by the vesting schedules of the DID
+        fn locked_balance(did: Did, currency_code: CurrencyCode) -> TokenBalance;
+    }
+}
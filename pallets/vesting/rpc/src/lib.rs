@@ -0,0 +1,145 @@
+//! RPC interface for the Vesting pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use did::Did;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use tokens::CurrencyCode;
+use vesting::VestingSchedule;
+pub use vesting_rpc_runtime_api::VestingApi as VestingRuntimeApi;
+
+/// A vesting schedule with its amount as a decimal string, JSON numbers can't hold every
+/// `TokenBalance`
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VestingScheduleResponse<BlockNumber> {
+    pub start: BlockNumber,
+    pub period: BlockNumber,
+    pub period_count: u32,
+    pub per_period: String,
+}
+
+impl<BlockNumber> From<VestingSchedule<BlockNumber>> for VestingScheduleResponse<BlockNumber> {
+    fn from(schedule: VestingSchedule<BlockNumber>) -> Self {
+        VestingScheduleResponse {
+            start: schedule.start,
+            period: schedule.period,
+            period_count: schedule.period_count,
+            per_period: schedule.per_period.to_string(),
+        }
+    }
+}
+
+/// Vesting schedules of a DID for a token
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VestingResponse<BlockNumber> {
+    pub schedules: Vec<VestingScheduleResponse<BlockNumber>>,
+    /// Amount still locked at the queried block as a decimal string, the rest of the vested
+    /// amount can be claimed
+    pub locked: String,
+}
+
+#[rpc]
+pub trait VestingApi<BlockHash, BlockNumber> {
+    /// Get the vesting schedules of the DID for the token with the given currency code
+    #[rpc(name = "vesting_schedules")]
+    fn schedules(
+        &self,
+        did: String,
+        currency_code: String,
+        at: Option<BlockHash>,
+    ) -> Result<VestingResponse<BlockNumber>>;
+}
+
+/// A struct that implements the `VestingApi`.
+pub struct Vesting<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Vesting<C, B> {
+    /// Create new `Vesting` instance with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The call to runtime failed.
+    RuntimeError,
+    /// The given DID or currency code is too long.
+    InvalidArgument,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::RuntimeError => 1,
+            Error::InvalidArgument => 2,
+        }
+    }
+}
+
+fn runtime_error<E: std::fmt::Debug>(e: E) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(Error::RuntimeError.into()),
+        message: "Unable to query vesting schedules.".into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+/// Pad the given string with zeros to a fixed size identifier
+fn to_padded<const N: usize>(value: &str, name: &str) -> Result<[u8; N]> {
+    let mut padded = [0; N];
+    if value.len() > N {
+        return Err(RpcError {
+            code: ErrorCode::ServerError(Error::InvalidArgument.into()),
+            message: format!("{} must be at most {} bytes.", name, N),
+            data: None,
+        });
+    }
+    padded[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(padded)
+}
+
+impl<C, Block, BlockNumber> VestingApi<<Block as BlockT>::Hash, BlockNumber> for Vesting<C, Block>
+where
+    Block: BlockT,
+    BlockNumber: Codec + Send + Sync + Serialize + 'static,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: VestingRuntimeApi<Block, BlockNumber>,
+{
+    fn schedules(
+        &self,
+        did: String,
+        currency_code: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<VestingResponse<BlockNumber>> {
+        let did: Did = to_padded(&did, "DID")?;
+        let currency_code: CurrencyCode = to_padded(&currency_code, "Currency code")?;
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let schedules = api
+            .vesting_schedules(&at, did, currency_code)
+            .map_err(runtime_error)?;
+        let locked = api
+            .locked_balance(&at, did, currency_code)
+            .map_err(runtime_error)?;
+        Ok(VestingResponse {
+            schedules: schedules.into_iter().map(Into::into).collect(),
+            locked: locked.to_string(),
+        })
+    }
+}
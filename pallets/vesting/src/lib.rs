@@ -0,0 +1,186 @@
+//! # Vesting Module
+//!
+//! ## Overview
+//!
+//! The vesting module lets token issuers distribute `tokens` currencies that unlock over time.
+//! The vested amount is transferred to the recipient straight away and held with a lock of the
+//! tokens module, the recipient claims the portions unlocked so far to release the lock.
+//!
+//! ### Dispatchable Functions
+//!
+//! - `vested_transfer` - Transfer tokens to a DID under a vesting schedule.
+//! - `claim` - Release the portion of the caller's vested tokens unlocked so far.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use did::Did;
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure, traits::Get, StorageDoubleMap,
+};
+use frame_system::ensure_signed;
+use orml_traits::{LockIdentifier, MultiCurrency, MultiLockableCurrency};
+use sp_runtime::{
+    traits::{AtLeast32Bit, Zero},
+    DispatchError, DispatchResult,
+};
+use sp_std::prelude::*;
+use tokens::{CurrencyCode, TokenBalance};
+
+#[cfg(test)]
+mod tests;
+mod structs;
+pub use crate::structs::*;
+
+/// Identifier of the tokens lock holding vested balances
+pub const VESTING_LOCK_ID: LockIdentifier = *b"ssidvest";
+
+pub trait Config: frame_system::Config + tokens::Config {
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
+
+    /// The minimum amount transferred under a vesting schedule
+    type MinVestedTransfer: Get<TokenBalance>;
+
+    /// Maximum number of schedules a DID can hold for a token
+    type MaxVestingSchedules: Get<u32>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Config> as Vesting {
+        /// Vesting schedules of a DID for a token
+        pub VestingSchedules get(fn vesting_schedules): double_map hasher(blake2_128_concat) Did, hasher(twox_64_concat) CurrencyCode => Vec<VestingSchedule<T::BlockNumber>>;
+    }
+}
+
+decl_event!(
+    pub enum Event<T> where BlockNumber = <T as frame_system::Config>::BlockNumber {
+        /// Tokens transferred under a vesting schedule [CurrencyCode, from, to, schedule]
+        VestingScheduleAdded(CurrencyCode, Did, Did, VestingSchedule<BlockNumber>),
+        /// Unlocked tokens claimed [CurrencyCode, did, still_locked]
+        Claimed(CurrencyCode, Did, TokenBalance),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Config> {
+        /// No token is registered with the currency code
+        TokenNotFound,
+        /// Only the token issuer can create vesting schedules
+        NotTokenIssuer,
+        /// The recipient DID is not registered
+        RecipientDIDNotRegistered,
+        /// Vesting period is zero
+        ZeroVestingPeriod,
+        /// Number of vesting periods is zero
+        ZeroVestingPeriodCount,
+        /// Total amount of the schedule overflows
+        NumOverflow,
+        /// Amount transferred is below `MinVestedTransfer`
+        AmountLow,
+        /// The recipient already holds the maximum number of schedules for the token
+        TooManyVestingSchedules,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Config> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        const MinVestedTransfer: TokenBalance = T::MinVestedTransfer::get();
+        const MaxVestingSchedules: u32 = T::MaxVestingSchedules::get();
+
+        fn deposit_event() = default;
+
+        /// Transfer tokens to a DID, locked under the given vesting schedule
+        ///
+        /// The dispatch origin for this call must be `Signed` by the token issuer.
+        #[weight = 1]
+        pub fn vested_transfer(
+            origin,
+            dest: Did,
+            currency_code: CurrencyCode,
+            schedule: VestingSchedule<T::BlockNumber>,
+        ) {
+            let sender = ensure_signed(origin)?;
+            ensure!(tokens::TokenData::contains_key(currency_code), Error::<T>::TokenNotFound);
+            let issuer = did::Module::<T>::get_did_from_account_id(&sender);
+            ensure!(tokens::Module::<T>::token_issuer(currency_code) == issuer, Error::<T>::NotTokenIssuer);
+            let to = did::Module::<T>::get_accountid_from_did(&dest)
+                .map_err(|_| Error::<T>::RecipientDIDNotRegistered)?;
+
+            ensure!(!schedule.period.is_zero(), Error::<T>::ZeroVestingPeriod);
+            ensure!(schedule.period_count > 0, Error::<T>::ZeroVestingPeriodCount);
+            let amount = schedule.total_amount().ok_or(Error::<T>::NumOverflow)?;
+            ensure!(amount >= T::MinVestedTransfer::get(), Error::<T>::AmountLow);
+
+            let mut schedules = Self::vesting_schedules(dest, currency_code);
+            ensure!(
+                (schedules.len() as u32) < T::MaxVestingSchedules::get(),
+                Error::<T>::TooManyVestingSchedules
+            );
+            schedules.push(schedule);
+
+            let currency_id = tokens::Module::<T>::token_info(currency_code);
+            <tokens::Module<T> as MultiCurrency<T::AccountId>>::transfer(currency_id, &sender, &to, amount)?;
+            Self::update_lock(dest, currency_code, &to, schedules)?;
+
+            Self::deposit_event(RawEvent::VestingScheduleAdded(currency_code, issuer, dest, schedule));
+        }
+
+        /// Release the portion of the caller's vested tokens unlocked so far
+        #[weight = 1]
+        pub fn claim(origin, currency_code: CurrencyCode) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let did = did::Module::<T>::get_did_from_account_id(&sender);
+
+            let schedules = Self::vesting_schedules(did, currency_code);
+            let locked = Self::update_lock(did, currency_code, &sender, schedules)?;
+
+            Self::deposit_event(RawEvent::Claimed(currency_code, did, locked));
+            Ok(())
+        }
+    }
+}
+
+impl<T: Config> Module<T> {
+    /// Amount of the token still locked by the vesting schedules of the DID
+    pub fn locked_balance(did: Did, currency_code: CurrencyCode) -> TokenBalance {
+        let now = <frame_system::Module<T>>::block_number();
+        Self::vesting_schedules(did, currency_code)
+            .iter()
+            .fold(Zero::zero(), |total: TokenBalance, schedule| {
+                total.saturating_add(schedule.locked_amount(now))
+            })
+    }
+
+    /// Drop the fully vested schedules and lock what the others still hold
+    ///
+    /// Returns the amount still locked
+    fn update_lock(
+        did: Did,
+        currency_code: CurrencyCode,
+        who: &T::AccountId,
+        schedules: Vec<VestingSchedule<T::BlockNumber>>,
+    ) -> Result<TokenBalance, DispatchError> {
+        let now = <frame_system::Module<T>>::block_number();
+        let currency_id = tokens::Module::<T>::token_info(currency_code);
+
+        let schedules: Vec<_> = schedules
+            .into_iter()
+            .filter(|schedule| !schedule.locked_amount(now).is_zero())
+            .collect();
+        let locked = schedules
+            .iter()
+            .fold(Zero::zero(), |total: TokenBalance, schedule| {
+                total.saturating_add(schedule.locked_amount(now))
+            });
+
+        if schedules.is_empty() {
+            <tokens::Module<T>>::remove_lock(VESTING_LOCK_ID, currency_id, who)?;
+            VestingSchedules::<T>::remove(did, currency_code);
+        } else {
+            <tokens::Module<T>>::set_lock(VESTING_LOCK_ID, currency_id, who, locked)?;
+            VestingSchedules::<T>::insert(did, currency_code, schedules);
+        }
+        Ok(locked)
+    }
+}
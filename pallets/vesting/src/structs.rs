@@ -0,0 +1,37 @@
+use super::*;
+use codec::{Decode, Encode};
+use sp_runtime::RuntimeDebug;
+
+/// Linear release of tokens: `per_period` unlocks at the end of each of the `period_count`
+/// periods following `start`. A cliff is a schedule with a single long period.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VestingSchedule<BlockNumber> {
+    /// Block from which the periods are counted
+    pub start: BlockNumber,
+    /// Number of blocks in a period
+    pub period: BlockNumber,
+    /// Number of periods before everything is unlocked
+    pub period_count: u32,
+    /// Amount unlocked at the end of each period
+    pub per_period: TokenBalance,
+}
+
+impl<BlockNumber: AtLeast32Bit + Copy> VestingSchedule<BlockNumber> {
+    /// Total amount of the schedule, `None` on overflow
+    pub fn total_amount(&self) -> Option<TokenBalance> {
+        self.per_period.checked_mul(self.period_count.into())
+    }
+
+    /// Amount still locked at block `now`
+    pub fn locked_amount(&self, now: BlockNumber) -> TokenBalance {
+        let elapsed_periods = now
+            .checked_sub(&self.start)
+            .map_or(Zero::zero(), |elapsed| elapsed / self.period);
+        let unlocked_periods: u32 = elapsed_periods
+            .min(self.period_count.into())
+            .unique_saturated_into();
+        let remaining_periods = self.period_count.saturating_sub(unlocked_periods);
+        self.per_period.saturating_mul(remaining_periods.into())
+    }
+}
@@ -0,0 +1,410 @@
+// Tests for vesting module
+use super::*;
+use crate as vesting;
+use frame_support::{
+    assert_noop, assert_ok, ord_parameter_types, parameter_types, traits::StorageMapShim,
+};
+use frame_system::EnsureSignedBy;
+use sp_core::{sr25519, H256};
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Module, Call, Config, Storage, Event<T>},
+        Did: did::{Module, Call, Storage, Event, Config},
+        ValidatorSet: validator_set::{Module, Call, Storage, Event, Config},
+        Balances: balances::{Module, Call, Storage, Event<T>, Config<T>},
+        VC: vc::{Module, Call, Storage, Event},
+        Tokens: tokens::{Module, Call, Storage, Event},
+        Vesting: vesting::{Module, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<u64>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+}
+
+impl did::Config for Test {
+    type Event = Event;
+}
+
+impl validator_set::Config for Test {
+    type Event = Event;
+    type ApproveOrigin = frame_system::EnsureRoot<u64>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 0;
+    pub const MaxLocks: u32 = 50;
+}
+
+impl balances::Config for Test {
+    type Balance = u64;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = StorageMapShim<
+        balances::Account<Test>,
+        frame_system::Provider<Test>,
+        u64,
+        balances::AccountData<u64>,
+    >;
+    type MaxLocks = MaxLocks;
+    type WeightInfo = ();
+    type DidResolution = did::Module<Test>;
+}
+
+parameter_types! {
+    pub const VCDeposit: u64 = 100;
+    pub const VCTombstoneDeposit: u64 = 10;
+    pub const VCMaxAge: u64 = 100;
+}
+
+ord_parameter_types! {
+    pub const CouncilElectedUser: u64 = ISSUER_ACCOUNT;
+}
+
+impl vc::Config for Test {
+    type Event = Event;
+    type ApproveOrigin = EnsureSignedBy<CouncilElectedUser, u64>;
+    type Currency = Balances;
+    type VCDeposit = VCDeposit;
+    type VCTombstoneDeposit = VCTombstoneDeposit;
+    type VCMaxAge = VCMaxAge;
+}
+
+parameter_types! {
+    pub const TreasuryReserveAmount: u64 = 1_000;
+    pub const MaxBatchTransfers: u32 = 3;
+    pub const MaxTokenMetadataLength: u32 = 32;
+}
+
+impl tokens::Config for Test {
+    type Event = Event;
+    type Amount = i64;
+    type CurrencyId = u32;
+    type WeightInfo = ();
+    type Currency = Balances;
+    type TreasuryReserve = TreasuryReserveAmount;
+    type VcVerifier = VC;
+    type MaxLocks = MaxLocks;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type MaxTokenMetadataLength = MaxTokenMetadataLength;
+    type TransferPolicy = tokens::RestrictionPolicy<Test>;
+}
+
+parameter_types! {
+    pub const MinVestedTransfer: TokenBalance = 10;
+    pub const MaxVestingSchedules: u32 = 2;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type MinVestedTransfer = MinVestedTransfer;
+    type MaxVestingSchedules = MaxVestingSchedules;
+}
+
+const ISSUER_ACCOUNT: u64 = 72340172838076673;
+const ISSUER_DID: did::Did = *b"did:ssid:issuer\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const ISSUER_PUBKEY: sr25519::Public = sr25519::Public([1; 32]);
+const RECIPIENT_ACCOUNT: u64 = 144680345676153346;
+const RECIPIENT_DID: did::Did = *b"did:ssid:recipient\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const RECIPIENT_PUBKEY: sr25519::Public = sr25519::Public([2; 32]);
+const UNKNOWN_DID: did::Did = *b"did:ssid:unknown\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const CURRENCY_ID: u32 = 1;
+const CURRENCY_CODE: CurrencyCode = *b"VST\0\0\0\0\0";
+const ISSUED_AMOUNT: TokenBalance = 10_000;
+
+// This function basically just builds a genesis storage key/value store according to
+// our desired mockup.
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut o = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap();
+
+    did::GenesisConfig {
+        dids: vec![
+            did::DidStruct {
+                identifier: ISSUER_DID,
+                public_key: ISSUER_PUBKEY,
+                metadata: vec![],
+            },
+            did::DidStruct {
+                identifier: RECIPIENT_DID,
+                public_key: RECIPIENT_PUBKEY,
+                metadata: vec![],
+            },
+        ],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    validator_set::GenesisConfig {
+        validators: vec![ISSUER_DID],
+    }
+    .assimilate_storage::<Test>(&mut o)
+    .unwrap();
+
+    let mut ext: sp_io::TestExternalities = o.into();
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        // register the token without going through a TokenVC
+        tokens::Module::<Test>::set_token_info(CURRENCY_ID, CURRENCY_CODE);
+        tokens::TokenData::insert(CURRENCY_CODE, tokens::TokenDetails::default());
+        tokens::TokenIssuer::insert(CURRENCY_CODE, ISSUER_DID);
+        assert_ok!(<Tokens as MultiCurrency<u64>>::deposit(
+            CURRENCY_ID,
+            &ISSUER_ACCOUNT,
+            ISSUED_AMOUNT
+        ));
+    });
+    ext
+}
+
+fn schedule(start: u64, period: u64, period_count: u32, per_period: TokenBalance) -> VestingSchedule<u64> {
+    VestingSchedule {
+        start,
+        period,
+        period_count,
+        per_period,
+    }
+}
+
+fn free_balance(who: u64) -> TokenBalance {
+    <Tokens as MultiCurrency<u64>>::free_balance(CURRENCY_ID, &who)
+}
+
+#[test]
+fn test_locked_amount() {
+    let linear = schedule(10, 5, 4, 100);
+    assert_eq!(linear.total_amount(), Some(400));
+    assert_eq!(linear.locked_amount(0), 400);
+    assert_eq!(linear.locked_amount(14), 400);
+    assert_eq!(linear.locked_amount(15), 300);
+    assert_eq!(linear.locked_amount(29), 100);
+    assert_eq!(linear.locked_amount(30), 0);
+    assert_eq!(linear.locked_amount(1_000), 0);
+
+    let cliff = schedule(10, 100, 1, 400);
+    assert_eq!(cliff.locked_amount(109), 400);
+    assert_eq!(cliff.locked_amount(110), 0);
+
+    assert_eq!(schedule(0, 1, u32::MAX, TokenBalance::MAX).total_amount(), None);
+}
+
+#[test]
+fn test_vested_transfer() {
+    new_test_ext().execute_with(|| {
+        let vesting = schedule(1, 10, 4, 100);
+        assert_ok!(Vesting::vested_transfer(
+            Origin::signed(ISSUER_ACCOUNT),
+            RECIPIENT_DID,
+            CURRENCY_CODE,
+            vesting
+        ));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::vesting(RawEvent::VestingScheduleAdded(
+                CURRENCY_CODE,
+                ISSUER_DID,
+                RECIPIENT_DID,
+                vesting
+            ))
+        );
+        assert_eq!(Vesting::vesting_schedules(RECIPIENT_DID, CURRENCY_CODE), vec![vesting]);
+        assert_eq!(free_balance(ISSUER_ACCOUNT), ISSUED_AMOUNT - 400);
+        assert_eq!(free_balance(RECIPIENT_ACCOUNT), 400);
+        assert_eq!(Vesting::locked_balance(RECIPIENT_DID, CURRENCY_CODE), 400);
+        assert_eq!(
+            tokens::Module::<Test>::accounts(RECIPIENT_DID, CURRENCY_CODE).data.frozen,
+            400
+        );
+
+        // the vested amount can't be moved
+        assert_noop!(
+            <Tokens as MultiCurrency<u64>>::transfer(CURRENCY_ID, &RECIPIENT_ACCOUNT, &ISSUER_ACCOUNT, 1),
+            tokens::Error::<Test>::LiquidityRestrictions
+        );
+    });
+}
+
+#[test]
+fn test_vested_transfer_validation() {
+    new_test_ext().execute_with(|| {
+        let transfer = |origin: u64, dest: did::Did, schedule: VestingSchedule<u64>| {
+            Vesting::vested_transfer(Origin::signed(origin), dest, CURRENCY_CODE, schedule)
+        };
+
+        assert_noop!(
+            Vesting::vested_transfer(
+                Origin::signed(ISSUER_ACCOUNT),
+                RECIPIENT_DID,
+                *b"NONE\0\0\0\0",
+                schedule(1, 10, 4, 100)
+            ),
+            Error::<Test>::TokenNotFound
+        );
+        assert_noop!(
+            transfer(RECIPIENT_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 4, 100)),
+            Error::<Test>::NotTokenIssuer
+        );
+        assert_noop!(
+            transfer(ISSUER_ACCOUNT, UNKNOWN_DID, schedule(1, 10, 4, 100)),
+            Error::<Test>::RecipientDIDNotRegistered
+        );
+        assert_noop!(
+            transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 0, 4, 100)),
+            Error::<Test>::ZeroVestingPeriod
+        );
+        assert_noop!(
+            transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 0, 100)),
+            Error::<Test>::ZeroVestingPeriodCount
+        );
+        assert_noop!(
+            transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 2, TokenBalance::MAX)),
+            Error::<Test>::NumOverflow
+        );
+        assert_noop!(
+            transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 1, 9)),
+            Error::<Test>::AmountLow
+        );
+        assert_noop!(
+            transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 1, ISSUED_AMOUNT + 1)),
+            tokens::Error::<Test>::BalanceTooLow
+        );
+
+        assert_ok!(transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 1, 100)));
+        assert_ok!(transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 1, 100)));
+        assert_noop!(
+            transfer(ISSUER_ACCOUNT, RECIPIENT_DID, schedule(1, 10, 1, 100)),
+            Error::<Test>::TooManyVestingSchedules
+        );
+    });
+}
+
+#[test]
+fn test_claim() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Vesting::vested_transfer(
+            Origin::signed(ISSUER_ACCOUNT),
+            RECIPIENT_DID,
+            CURRENCY_CODE,
+            schedule(1, 10, 4, 100)
+        ));
+        // a cliff releasing everything at block 31
+        assert_ok!(Vesting::vested_transfer(
+            Origin::signed(ISSUER_ACCOUNT),
+            RECIPIENT_DID,
+            CURRENCY_CODE,
+            schedule(1, 30, 1, 50)
+        ));
+
+        System::set_block_number(11);
+        assert_ok!(Vesting::claim(Origin::signed(RECIPIENT_ACCOUNT), CURRENCY_CODE));
+        assert_eq!(
+            System::events().last().unwrap().event,
+            Event::vesting(RawEvent::Claimed(CURRENCY_CODE, RECIPIENT_DID, 350))
+        );
+        assert_eq!(
+            tokens::Module::<Test>::accounts(RECIPIENT_DID, CURRENCY_CODE).data.frozen,
+            350
+        );
+        assert_ok!(<Tokens as MultiCurrency<u64>>::transfer(
+            CURRENCY_ID,
+            &RECIPIENT_ACCOUNT,
+            &ISSUER_ACCOUNT,
+            100
+        ));
+
+        // the cliff is fully vested and dropped
+        System::set_block_number(31);
+        assert_ok!(Vesting::claim(Origin::signed(RECIPIENT_ACCOUNT), CURRENCY_CODE));
+        assert_eq!(Vesting::locked_balance(RECIPIENT_DID, CURRENCY_CODE), 100);
+        assert_eq!(Vesting::vesting_schedules(RECIPIENT_DID, CURRENCY_CODE).len(), 1);
+
+        System::set_block_number(41);
+        assert_ok!(Vesting::claim(Origin::signed(RECIPIENT_ACCOUNT), CURRENCY_CODE));
+        assert!(!VestingSchedules::<Test>::contains_key(RECIPIENT_DID, CURRENCY_CODE));
+        assert!(tokens::Module::<Test>::locks(RECIPIENT_DID, CURRENCY_CODE).is_empty());
+        assert_eq!(
+            tokens::Module::<Test>::accounts(RECIPIENT_DID, CURRENCY_CODE).data.frozen,
+            0
+        );
+        assert_ok!(<Tokens as MultiCurrency<u64>>::transfer(
+            CURRENCY_ID,
+            &RECIPIENT_ACCOUNT,
+            &ISSUER_ACCOUNT,
+            350
+        ));
+    });
+}
+
+#[test]
+fn test_claim_after_key_rotation() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Vesting::vested_transfer(
+            Origin::signed(ISSUER_ACCOUNT),
+            RECIPIENT_DID,
+            CURRENCY_CODE,
+            schedule(1, 10, 4, 100)
+        ));
+
+        let new_pubkey = sr25519::Public([3; 32]);
+        assert_ok!(Did::rotate_key(Origin::signed(ISSUER_ACCOUNT), RECIPIENT_DID, new_pubkey));
+        let new_account = did::Module::<Test>::get_accountid_from_pubkey(&new_pubkey);
+
+        // the lock set under the old key still holds the balance
+        assert_noop!(
+            <Tokens as MultiCurrency<u64>>::transfer(CURRENCY_ID, &new_account, &ISSUER_ACCOUNT, 1),
+            tokens::Error::<Test>::LiquidityRestrictions
+        );
+
+        System::set_block_number(41);
+        assert_ok!(Vesting::claim(Origin::signed(new_account), CURRENCY_CODE));
+        assert!(!VestingSchedules::<Test>::contains_key(RECIPIENT_DID, CURRENCY_CODE));
+        assert!(tokens::Module::<Test>::locks(RECIPIENT_DID, CURRENCY_CODE).is_empty());
+        assert_ok!(<Tokens as MultiCurrency<u64>>::transfer(
+            CURRENCY_ID,
+            &new_account,
+            &ISSUER_ACCOUNT,
+            400
+        ));
+    });
+}
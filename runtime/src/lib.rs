@@ -18,7 +18,7 @@ use sp_runtime::{
 };
 use sp_std::prelude::*;
 
-use frame_system::{EnsureOneOf, EnsureRoot};
+use frame_system::{EnsureOneOf, EnsureRoot, EnsureSigned};
 use pallet_grandpa::fg_primitives;
 use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use sp_api::impl_runtime_apis;
@@ -52,10 +52,13 @@ pub use tokens;
 pub use validator_set;
 pub use vc;
 pub use verified_credential;
+pub use vesting;
+pub use swap;
 //pub use treasury;
 
 /// Constant values used within the runtime.
 pub mod constants;
+use constants::currency::deposit;
 
 /// An index to a block.
 pub type BlockNumber = u32;
@@ -111,10 +114,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("metablockchain-runtime"),
     impl_name: create_runtime_str!("metablockchain-runtime"),
     authoring_version: 1,
-    spec_version: 7,
+    spec_version: 8,
     impl_version: 5,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
 };
 
 pub const MILLISECS_PER_BLOCK: u64 = 5000;
@@ -296,6 +299,8 @@ impl node_authorization::Config for Runtime {
     type RemoveOrigin = EnsureRoot<AccountId>;
     type SwapOrigin = EnsureRoot<AccountId>;
     type ResetOrigin = EnsureRoot<AccountId>;
+    // use `vc::EnsureHasCredential` to require an unexpired credential for claiming nodes
+    type ClaimOrigin = EnsureSigned<AccountId>;
     type WeightInfo = ();
 }
 
@@ -312,12 +317,28 @@ impl did::Config for Runtime {
     //type OnKilledAccount = ();
 }
 
+parameter_types! {
+    pub const MaxSchemaLength: u32 = 16 * 1024;
+    pub const SchemaDepositBase: Balance = deposit(1, 0);
+    pub const SchemaDepositPerByte: Balance = deposit(0, 1);
+}
+
 impl schema::Config for Runtime {
     type Event = Event;
+    type MaxSchemaLength = MaxSchemaLength;
+    type Currency = Balances;
+    type SchemaDepositBase = SchemaDepositBase;
+    type SchemaDepositPerByte = SchemaDepositPerByte;
+}
+
+parameter_types! {
+    pub const MaxReasonLength: u32 = 256;
 }
 
 impl verified_credential::Config for Runtime {
     type Event = Event;
+    type Time = Timestamp;
+    type MaxReasonLength = MaxReasonLength;
 }
 
 // validator_set pallet
@@ -331,6 +352,12 @@ impl validator_set::Config for Runtime {
 }
 
 type CurrencyId = u32;
+
+parameter_types! {
+    pub const MaxBatchTransfers: u32 = 100;
+    pub const MaxTokenMetadataLength: u32 = 256;
+}
+
 // token pallet
 impl tokens::Config for Runtime {
     type Event = Event;
@@ -339,6 +366,39 @@ impl tokens::Config for Runtime {
     type WeightInfo = ();
     type Currency = Balances;
     type TreasuryReserve = TreasuryReserveAmount;
+    type VcVerifier = VC;
+    type MaxLocks = MaxLocks;
+    type MaxBatchTransfers = MaxBatchTransfers;
+    type MaxTokenMetadataLength = MaxTokenMetadataLength;
+    type TransferPolicy = tokens::RestrictionPolicy<Runtime>;
+}
+
+parameter_types! {
+    pub const MinVestedTransfer: tokens::TokenBalance = 1;
+    pub const MaxVestingSchedules: u32 = 10;
+}
+
+impl vesting::Config for Runtime {
+    type Event = Event;
+    type MinVestedTransfer = MinVestedTransfer;
+    type MaxVestingSchedules = MaxVestingSchedules;
+}
+
+parameter_types! {
+    pub const MaxSecretLength: u32 = 64;
+}
+
+impl swap::Config for Runtime {
+    type Event = Event;
+    type MaxSecretLength = MaxSecretLength;
+}
+
+parameter_types! {
+    // a single issuer VC takes roughly 300 bytes of storage
+    pub const VCDeposit: Balance = deposit(1, 300);
+    // a removed VC leaves a tombstone of its hashed id and flag behind
+    pub const VCTombstoneDeposit: Balance = deposit(1, 49);
+    pub const VCMaxAge: BlockNumber = 30 * DAYS;
 }
 
 // vc pallet
@@ -349,6 +409,10 @@ impl vc::Config for Runtime {
         EnsureRoot<AccountId>,
         collective::EnsureProportionMoreThan<_1, _2, AccountId>,
     >;
+    type Currency = Balances;
+    type VCDeposit = VCDeposit;
+    type VCTombstoneDeposit = VCTombstoneDeposit;
+    type VCMaxAge = VCMaxAge;
 }
 
 // collective pallet
@@ -421,11 +485,13 @@ construct_runtime!(
         NodeAuthorization: node_authorization::{Module, Call, Storage, Event, Config},
         Did: did::{Module, Call, Storage, Event, Config},
         Schema: schema::{Module, Call, Storage, Event<T>},
-        VerifiedCredential: verified_credential::{Module, Call, Storage, Event<T>},
+        VerifiedCredential: verified_credential::{Module, Call, Storage, Event<T>, Config},
         ValidatorSet: validator_set::{Module, Call, Storage, Event, Config},
-        VC: vc::{Module, Call, Storage, Event},
+        VC: vc::{Module, Call, Storage, Event, Config},
         Council: collective::{Module, Call, Storage, Origin<T>, Event<T>, Config},
-        Tokens: tokens::{Module, Call, Storage, Event},
+        Tokens: tokens::{Module, Call, Storage, Event, Config},
+        Vesting: vesting::{Module, Call, Storage, Event<T>},
+        Swap: swap::{Module, Call, Storage, Event<T>},
         //Treasury: treasury::{Module, Call, Storage, Event<T>},
     }
 );
@@ -593,6 +659,75 @@ impl_runtime_apis! {
         }
     }
 
+    impl vc_rpc_runtime_api::VcApi<Block> for Runtime {
+        fn pending_issuers(vc_id: vc::VCid) -> Option<Vec<vc::Did>> {
+            VC::get_pending_issuers(vc_id)
+        }
+    }
+
+    impl schema_rpc_runtime_api::SchemaApi<Block, Hash> for Runtime {
+        fn get_schema(hash: Hash) -> Option<schema::SchemaDetails<Hash>> {
+            Schema::get_schema_details(hash)
+        }
+
+        fn list_by_author(author: did::Did) -> Vec<schema::SchemaDetails<Hash>> {
+            Schema::get_schemas_by_author(author)
+        }
+
+        fn search_by_name(name: Vec<u8>) -> Vec<schema::SchemaDetails<Hash>> {
+            Schema::search_schemas_by_name(name)
+        }
+    }
+
+    impl tokens_rpc_runtime_api::TokensApi<Block> for Runtime {
+        fn token_metadata(currency_code: tokens::CurrencyCode) -> Option<tokens::TokenMetadataDetails> {
+            Tokens::get_token_metadata(currency_code)
+        }
+
+        fn allowance(owner: did::Did, spender: did::Did, currency_code: tokens::CurrencyCode) -> tokens::TokenBalance {
+            Tokens::allowance((owner, spender), currency_code)
+        }
+
+        fn mintable_supply(currency_code: tokens::CurrencyCode) -> Option<tokens::MintableSupply> {
+            Tokens::mintable_supply(currency_code)
+        }
+    }
+
+    impl vesting_rpc_runtime_api::VestingApi<Block, BlockNumber> for Runtime {
+        fn vesting_schedules(
+            did: did::Did,
+            currency_code: tokens::CurrencyCode,
+        ) -> Vec<vesting::VestingSchedule<BlockNumber>> {
+            Vesting::vesting_schedules(did, currency_code)
+        }
+
+        fn locked_balance(did: did::Did, currency_code: tokens::CurrencyCode) -> tokens::TokenBalance {
+            Vesting::locked_balance(did, currency_code)
+        }
+    }
+
+    impl verified_credential_rpc_runtime_api::VerifiedCredentialApi<Block, Hash, BlockNumber, u64> for Runtime {
+        fn get_anchor(vc_hash: Hash) -> Option<verified_credential::CredentialAnchor<Hash, BlockNumber>> {
+            VerifiedCredential::vc(vc_hash)
+        }
+
+        fn credential_status(vc_hash: Hash) -> verified_credential::CredentialStatus {
+            VerifiedCredential::credential_status(&vc_hash)
+        }
+
+        fn get_revocation(vc_hash: Hash) -> Option<verified_credential::Revocation<BlockNumber, u64>> {
+            VerifiedCredential::revocation(vc_hash)
+        }
+
+        fn verify_batch(
+            root: Hash,
+            vc_hash: Hash,
+            proof: Vec<Hash>,
+        ) -> Option<verified_credential::BatchAnchor<Hash, BlockNumber>> {
+            VerifiedCredential::verify_batch(&root, &vc_hash, &proof)
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn dispatch_benchmark(